@@ -1,5 +1,3 @@
-use hash_collections;
-
 use std::error::Error;
 use std::fs::File;
 use std::io::{self, BufRead, BufReader, ErrorKind, Write};
@@ -58,9 +56,9 @@ impl SentenceIterator {
                 .collect();
            
             if !sentences.is_empty() { 
-                for i in 0..sentences.len()-1 {
+                for sentence in &sentences[..sentences.len()-1] {
                     self.sentences_buffer.push_back({
-                        Sentence::Complete(sentences[i].to_string())
+                        Sentence::Complete(sentence.to_string())
                     });
                 }
 
@@ -84,12 +82,8 @@ impl Iterator for SentenceIterator {
     type Item = String;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            if let Some(Sentence::Incomplete(_)) = self.sentences_buffer.front() {
-                self.read_sentences();
-            } else {
-                break;
-            }
+        while let Some(Sentence::Incomplete(_)) = self.sentences_buffer.front() {
+            self.read_sentences();
         }
         self.sentences_buffer.pop_front().map(|s| s.take())
     }
@@ -106,12 +100,12 @@ fn prompt_for_input(prompt: &str) -> String {
 
 fn main() -> Result<(), Box<dyn Error>> {
     let input_file_path = prompt_for_input("Enter file name");
-    if input_file_path.len() == 0 {
+    if input_file_path.is_empty() {
         println!("No file name provided. Terminating!!");
         return Err(std::io::Error::new(ErrorKind::InvalidFilename, "file name not provided").into())
     }
 
-    let mut graph = hash_collections::FixedSizeHashGraphMap::<String, bool, 50849>::new();
+    let mut graph = hash_collections::FixedSizeHashGraphMap::<String, bool, 200003>::new();
 
     println!("Parsing file ...");
     let sentence_iter = SentenceIterator::new(input_file_path.as_str())?;
@@ -137,21 +131,21 @@ fn main() -> Result<(), Box<dyn Error>> {
                 )?
             }
 
-            graph.connect_to(&words[i].to_string(), vec![&sentence]);
+            graph.connect_to(words[i], vec![sentence.as_str()]);
         }
     }
 
     loop {
         let input_word = prompt_for_input("Enter a word");
-        if input_word.len() > 0 {
+        if !input_word.is_empty() {
             if let Some(node) = graph.node(&input_word) {
                 println!("  '{}' connected to words:", input_word);
-                for (word_node, w) in node.iter_out_edges().filter(|(n, _)| *n.value()==false){
+                for (word_node, w) in node.iter_out_edges().filter(|(n, _)| !*n.value()){
                     println!("    {}, ({})", word_node.key(), w);
                 }
 
                 println!("  '{}' found in sentences:", input_word);
-                for (word_node, w) in node.iter_out_edges().filter(|(n, _)| *n.value()==true){
+                for (word_node, w) in node.iter_out_edges().filter(|(n, _)| *n.value()){
                     println!("    {}, ({})", word_node.key(), w);
                 }
             } else {
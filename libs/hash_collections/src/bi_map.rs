@@ -0,0 +1,398 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_prime_and_within_limit};
+use crate::{
+    hash_map::{DynFixedHashMap, MapIter},
+    probe::{LinearProbe, Probe},
+    errors::{BiMapInsertError, InsertError},
+};
+
+// One-to-one map between left and right values. Two tables of the same capacity are kept in
+// sync, left to right and right to left, so both sides are stored twice. Pairs are listed in
+// insertion order.
+pub struct DynFixedBiMap<L, R, H = DefaultHasher, P = LinearProbe>
+where
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _left_to_right: DynFixedHashMap<L, R, H, P>,
+    _right_to_left: DynFixedHashMap<R, L, H, P>,
+}
+
+impl<L, R, H, P> DynFixedBiMap<L, R, H, P>
+where
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new(capacity: usize) -> DynFixedBiMap<L, R, H, P> {
+        DynFixedBiMap {
+            _left_to_right: DynFixedHashMap::new(capacity),
+            _right_to_left: DynFixedHashMap::new(capacity),
+        }
+    }
+
+    // Pairs left with right. Returns false when the pair is already there. Fails without
+    // changing anything when either value is paired with something else or the pair does not
+    // fit.
+    pub fn insert(&mut self, left: L, right: R) -> Result<bool, BiMapInsertError<L, R>>
+    where
+        L: Clone,
+        R: Clone,
+    {
+        let paired_right = self._left_to_right.get(&left);
+        let paired_left = self._right_to_left.get(&right);
+        match (paired_right, paired_left) {
+            (Some(paired_right), Some(_)) if *paired_right == right => return Ok(false),
+            (Some(_), Some(_)) => return Err(BiMapInsertError::BothConflict(left, right)),
+            (Some(_), None) => return Err(BiMapInsertError::LeftConflict(left, right)),
+            (None, Some(_)) => return Err(BiMapInsertError::RightConflict(left, right)),
+            (None, None) => {}
+        }
+
+        if self.size() == self.capacity() {
+            return Err(BiMapInsertError::OutOfCapacity(InsertError {
+                capacity: self.capacity(),
+                key: left,
+                value: right,
+            }));
+        }
+
+        self._left_to_right
+            .insert(left.clone(), right.clone())
+            .map_err(BiMapInsertError::OutOfCapacity)?;
+        if let Err(error) = self._right_to_left.insert(right, left) {
+            self._left_to_right.remove(&error.value);
+            return Err(BiMapInsertError::OutOfCapacity(InsertError {
+                capacity: error.capacity,
+                key: error.value,
+                value: error.key,
+            }));
+        }
+        Ok(true)
+    }
+
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._left_to_right.get(left)
+    }
+
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._right_to_left.get(right)
+    }
+
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._left_to_right.exists(left)
+    }
+
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._right_to_left.exists(right)
+    }
+
+    // Removes the pair from both tables and hands it back.
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let right = self._left_to_right.remove(left)?;
+        let left = self._right_to_left.remove(&right)?;
+        Some((left, right))
+    }
+
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let left = self._right_to_left.remove(right)?;
+        let right = self._left_to_right.remove(&left)?;
+        Some((left, right))
+    }
+
+    // Inserts pairs until one conflicts or does not fit. Pairs before it stay inserted, the ones
+    // after it are not consumed.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), BiMapInsertError<L, R>>
+    where
+        I: IntoIterator<Item = (L, R)>,
+        L: Clone,
+        R: Clone,
+    {
+        for (left, right) in iter {
+            self.insert(left, right)?;
+        }
+        Ok(())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._left_to_right.capacity()
+    }
+
+    pub fn size(&self) -> usize {
+        self._left_to_right.size()
+    }
+
+    pub fn iter_head(&self) -> MapIter<'_, L, R> {
+        self._left_to_right.iter_head()
+    }
+
+    pub fn iter_tail(&self) -> MapIter<'_, L, R> {
+        self._left_to_right.iter_tail()
+    }
+
+    pub fn clear(&mut self) {
+        self._left_to_right.clear();
+        self._right_to_left.clear();
+    }
+}
+
+impl<L, R, H, P> Clone for DynFixedBiMap<L, R, H, P>
+where
+    L: Hash + std::cmp::Eq + Clone,
+    R: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _left_to_right: self._left_to_right.clone(),
+            _right_to_left: self._right_to_left.clone(),
+        }
+    }
+}
+
+impl<L, R, H, P> fmt::Debug for DynFixedBiMap<L, R, H, P>
+where
+    L: Hash + std::cmp::Eq + fmt::Debug,
+    R: Hash + std::cmp::Eq + fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self._left_to_right, f)
+    }
+}
+
+// Bimaps are equal when they hold the same pairs, whatever their order or capacity.
+impl<L, R, H, P> PartialEq for DynFixedBiMap<L, R, H, P>
+where
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self._left_to_right == other._left_to_right
+    }
+}
+
+impl<L, R, H, P> Eq for DynFixedBiMap<L, R, H, P>
+where
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+// Panics on a conflict or once the bimap is full, use try_extend to get the rejected pair back
+// instead.
+impl<L, R, H, P> Extend<(L, R)> for DynFixedBiMap<L, R, H, P>
+where
+    L: Hash + std::cmp::Eq + Clone,
+    R: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = (L, R)>>(&mut self, iter: I) {
+        if let Err(error) = self.try_extend(iter) {
+            panic!("{}", error);
+        }
+    }
+}
+
+// Compile time checked capacity. All bimap operations are provided by the DynFixedBiMap it
+// dereferences to.
+#[cfg(feature = "nightly")]
+pub struct FixedSizeBiMap<L, R, const C: usize, H = DefaultHasher, P = LinearProbe>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _bi_map: DynFixedBiMap<L, R, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> Default for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    const CAPACITY: usize = C;
+
+    pub fn new() -> FixedSizeBiMap<L, R, C, H, P> {
+        FixedSizeBiMap {
+            _bi_map: DynFixedBiMap::new(C),
+        }
+    }
+
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, BiMapInsertError<L, R>>
+    where
+        I: IntoIterator<Item = (L, R)>,
+        L: Clone,
+        R: Clone,
+    {
+        let mut bi_map = Self::new();
+        bi_map.try_extend(iter)?;
+        Ok(bi_map)
+    }
+
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> Deref for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynFixedBiMap<L, R, H, P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self._bi_map
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> DerefMut for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._bi_map
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> Clone for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq + Clone,
+    R: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _bi_map: self._bi_map.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> fmt::Debug for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq + fmt::Debug,
+    R: Hash + std::cmp::Eq + fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self._bi_map, f)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> PartialEq for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self._bi_map == other._bi_map
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> Eq for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq,
+    R: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+#[cfg(feature = "nightly")]
+impl<L, R, const C: usize, H, P> Extend<(L, R)> for FixedSizeBiMap<L, R, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    L: Hash + std::cmp::Eq + Clone,
+    R: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = (L, R)>>(&mut self, iter: I) {
+        self._bi_map.extend(iter);
+    }
+}
+
@@ -1,10 +1,30 @@
-use const_primes::is_prime;
+use const_primes::{is_prime, next_prime};
 
+#[cfg(feature = "nightly")]
 pub struct Check<const U: bool>;
+#[cfg(feature = "nightly")]
 pub trait IsTrue {}
 
+#[cfg(feature = "nightly")]
 impl IsTrue for Check<true> {}
 
+#[cfg(feature = "nightly")]
 pub const fn is_prime_and_within_limit(c: usize, max_cap: usize) -> bool {
     is_prime(c as u64) && c <= max_cap
 }
+
+// For types whose C counts entries and that size their own prime slot table from it, and for
+// capacities read back from snapshots.
+pub const fn is_nonzero_and_within_limit(c: usize, max_cap: usize) -> bool {
+    c > 0 && c <= max_cap
+}
+
+pub const fn prime_at_least(c: usize) -> usize {
+    if is_prime(c as u64) {
+        return c;
+    }
+    match next_prime(c as u64) {
+        Some(prime) => prime as usize,
+        None => panic!("no prime capacity fits in u64"),
+    }
+}
@@ -0,0 +1,311 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::hash::{DefaultHasher, Hash, Hasher};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_nonzero_and_within_limit};
+#[cfg(feature = "nightly")]
+use crate::eviction::forward_eviction_policy;
+use crate::{
+    eviction::{CacheEntry, EvictionPolicy, cache_table_capacity},
+    hash_map_internal::{Entry, FixedSizeHashMapImpl, SlotLookup},
+    probe::{LinearProbe, Probe},
+};
+
+// Second chance eviction: every access sets the entry's reference bit, and the hand sweeps the
+// slot array clearing bits until it finds an entry that was not referenced since its last pass.
+pub struct DynClockCache<K, V, H = DefaultHasher, P = LinearProbe>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _hash_map_internal: FixedSizeHashMapImpl<K, V, H, CacheEntry<K, V>, P>,
+    _hand: usize,
+    _capacity: usize,
+    _on_evict: Option<Box<dyn FnMut(K, V)>>,
+}
+
+impl<K, V, H, P> DynClockCache<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new(capacity: usize) -> DynClockCache<K, V, H, P> {
+        DynClockCache::<K, V, H, P> {
+            _hash_map_internal: FixedSizeHashMapImpl::new(cache_table_capacity(capacity)),
+            _hand: 0,
+            _capacity: capacity,
+            _on_evict: None,
+        }
+    }
+
+    pub fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F) {
+        self._on_evict = Some(Box::new(callback));
+    }
+
+    // New and updated entries start out referenced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self._hash_map_internal.hash_of(&key);
+        let mut slot = self._hash_map_internal.find_slot(hash, &key);
+        if let SlotLookup::Occupied(index) = slot {
+            return self._hash_map_internal.get_mut_entry_at(index).map(|entry| {
+                *entry.mut_uses() = 1;
+                std::mem::replace(entry.mut_value(), value)
+            });
+        }
+
+        if self.size() >= self._capacity {
+            while self.size() >= self._capacity {
+                let Some((old_key, old_value)) = self.evict() else {
+                    // A zero capacity cache evicts everything straight away.
+                    self._evicted(key, value);
+                    return None;
+                };
+                self._evicted(old_key, old_value);
+            }
+            // evict can compact the table, so look the slot up again.
+            slot = self._hash_map_internal.find_slot(hash, &key);
+        }
+
+        let SlotLookup::Vacant(index) = slot else {
+            unreachable!("key was looked up above");
+        };
+        match self._hash_map_internal.insert_at(index, hash, key, value) {
+            Ok(entry) => *entry.mut_uses() = 1,
+            Err(error) => {
+                let (key, value) = error.into_inner();
+                self._evicted(key, value);
+            }
+        }
+        None
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let (entry, _) = self._hash_map_internal.get_mut_entry_and_index_of(key)?;
+        *entry.mut_uses() = 1;
+        Some(entry.mut_value())
+    }
+
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .get_entry_and_index_of(key)
+            .map(|e| e.0.value())
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .remove(key)
+            .map(|e| e.consume_self())
+    }
+
+    // Advances the hand to the first unreferenced entry and removes it. Every referenced entry
+    // passed on the way loses its reference bit, so at most two sweeps are needed.
+    pub fn evict(&mut self) -> Option<(K, V)> {
+        if self.size() == 0 {
+            return None;
+        }
+
+        let slots = self._hash_map_internal.capacity();
+        loop {
+            let i = self._hand;
+            self._hand = (i + 1) % slots;
+            match self._hash_map_internal.get_mut_entry_at(i) {
+                Some(entry) if entry.uses() != 0 => *entry.mut_uses() = 0,
+                Some(_) => {
+                    return self
+                        ._hash_map_internal
+                        .remove_at(i)
+                        .map(|e| e.into_key_value());
+                }
+                None => {}
+            }
+        }
+    }
+
+    pub fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal.exists(key)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._capacity
+    }
+
+    pub fn size(&self) -> usize {
+        self._hash_map_internal.size()
+    }
+
+    fn _evicted(&mut self, key: K, value: V) {
+        if let Some(callback) = self._on_evict.as_mut() {
+            callback(key, value);
+        }
+    }
+}
+
+impl<K, V, H, P> EvictionPolicy<K, V> for DynClockCache<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.get(key)
+    }
+
+    fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.peek(key)
+    }
+
+    fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.remove(key)
+    }
+
+    fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.exists(key)
+    }
+
+    fn evict(&mut self) -> Option<(K, V)> {
+        self.evict()
+    }
+
+    fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F) {
+        self.set_eviction_callback(callback)
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+}
+
+#[cfg(feature = "nightly")]
+pub struct ClockCache<K, V, const C: usize, H = DefaultHasher, P = LinearProbe>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _cache: DynClockCache<K, V, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Default for ClockCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> ClockCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new() -> ClockCache<K, V, C, H, P> {
+        ClockCache::<K, V, C, H, P> {
+            _cache: DynClockCache::new(C),
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        C
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Deref for ClockCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynClockCache<K, V, H, P>;
+    fn deref(&self) -> &Self::Target {
+        &self._cache
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> DerefMut for ClockCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._cache
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> EvictionPolicy<K, V> for ClockCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    forward_eviction_policy!();
+}
@@ -0,0 +1,478 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::{BitAnd, BitOr, Sub};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_prime_and_within_limit};
+use crate::{
+    hash_map::{DynFixedHashMap, MapIter},
+    probe::{LinearProbe, Probe},
+    errors::{InsertError, OutOfCapacityError},
+};
+
+// Counts occurrences of items in a DynFixedHashMap<T, u32>. Items are listed in the order they
+// were first counted and are dropped once their count reaches zero. Counts saturate at u32::MAX.
+pub struct DynFixedCounter<T, H = DefaultHasher, P = LinearProbe>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _counts: DynFixedHashMap<T, u32, H, P>,
+    _total: u64,
+}
+
+impl<T, H, P> DynFixedCounter<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn placeholder(capacity: usize) -> DynFixedCounter<T, H, P> {
+        DynFixedCounter {
+            _counts: DynFixedHashMap::placeholder(capacity),
+            _total: 0,
+        }
+    }
+
+    pub fn new(capacity: usize) -> DynFixedCounter<T, H, P> {
+        DynFixedCounter {
+            _counts: DynFixedHashMap::new(capacity),
+            _total: 0,
+        }
+    }
+
+    // Adds n to the count of item and returns the new count. Adding zero to a missing item
+    // leaves the counter unchanged.
+    pub fn add(&mut self, item: T, n: u32) -> Result<u32, InsertError<T, u32>> {
+        if n == 0 {
+            return Ok(self.count(&item));
+        }
+
+        let count = self
+            ._counts
+            .entry(item)
+            .or_insert(0)
+            .map_err(|error| InsertError { value: n, ..error })?;
+        let old_count = *count;
+        *count = old_count.saturating_add(n);
+        let new_count = *count;
+        self._total += u64::from(new_count - old_count);
+        Ok(new_count)
+    }
+
+    // Takes up to n off the count of item, removing it at zero, and returns what is left.
+    pub fn subtract<Q>(&mut self, item: &Q, n: u32) -> u32
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(count) = self._counts.get_mut(item) else {
+            return 0;
+        };
+        let taken = n.min(*count);
+        *count -= taken;
+        let new_count = *count;
+        self._total -= u64::from(taken);
+        if new_count == 0 {
+            self._counts.remove(item);
+        }
+        new_count
+    }
+
+    // Drops item whatever its count and returns the count it had.
+    pub fn remove<Q>(&mut self, item: &Q) -> u32
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let count = self._counts.remove(item).unwrap_or(0);
+        self._total -= u64::from(count);
+        count
+    }
+
+    pub fn count<Q>(&self, item: &Q) -> u32
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._counts.get(item).map_or(0, |count| *count)
+    }
+
+    pub fn exists<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._counts.exists(item)
+    }
+
+    // Sum of all counts.
+    pub fn total(&self) -> u64 {
+        self._total
+    }
+
+    // The k highest counts, ties in the order the items were first counted.
+    pub fn most_common(&self, k: usize) -> Vec<(&T, u32)> {
+        let mut items = self
+            ._counts
+            .iter_head()
+            .map(|(item, count)| (item, *count))
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        items.truncate(k);
+        items
+    }
+
+    // Counts every item once. Items before the one that does not fit stay counted, the ones
+    // after it are not consumed.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), InsertError<T, u32>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iter {
+            self.add(item, 1)?;
+        }
+        Ok(())
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._counts.capacity()
+    }
+
+    // Number of distinct items.
+    pub fn size(&self) -> usize {
+        self._counts.size()
+    }
+
+    pub fn iter_head(&self) -> MapIter<'_, T, u32> {
+        self._counts.iter_head()
+    }
+
+    pub fn iter_tail(&self) -> MapIter<'_, T, u32> {
+        self._counts.iter_tail()
+    }
+
+    pub fn clear(&mut self) {
+        self._counts.clear();
+        self._total = 0;
+    }
+
+    // Counter arithmetic into a new counter with the capacity of self. Only positive counts are
+    // kept. sum adds counts, difference subtracts them, union keeps the larger and intersection the
+    // smaller of the two.
+    pub fn sum(&self, other: &DynFixedCounter<T, H, P>) -> Result<Self, OutOfCapacityError>
+    where
+        T: Clone,
+    {
+        self._combine(other, self.capacity(), u32::saturating_add)
+    }
+
+    pub fn difference(&self, other: &DynFixedCounter<T, H, P>) -> Result<Self, OutOfCapacityError>
+    where
+        T: Clone,
+    {
+        self._combine(other, self.capacity(), u32::saturating_sub)
+    }
+
+    pub fn union(&self, other: &DynFixedCounter<T, H, P>) -> Result<Self, OutOfCapacityError>
+    where
+        T: Clone,
+    {
+        self._combine(other, self.capacity(), u32::max)
+    }
+
+    pub fn intersection(&self, other: &DynFixedCounter<T, H, P>) -> Result<Self, OutOfCapacityError>
+    where
+        T: Clone,
+    {
+        self._combine(other, self.capacity(), u32::min)
+    }
+
+    // Builds a counter of the given capacity with op applied to the counts of every item in
+    // either counter, self's items first. Items whose result is zero are left out.
+    fn _combine<F>(
+        &self,
+        other: &DynFixedCounter<T, H, P>,
+        capacity: usize,
+        op: F,
+    ) -> Result<DynFixedCounter<T, H, P>, OutOfCapacityError>
+    where
+        T: Clone,
+        F: Fn(u32, u32) -> u32,
+    {
+        let mut combined = DynFixedCounter::new(capacity);
+        let left = self.iter_head().map(|(item, count)| (item, op(*count, other.count(item))));
+        let right_only = other
+            .iter_head()
+            .filter(|(item, _)| !self.exists(*item))
+            .map(|(item, count)| (item, op(0, *count)));
+        for (item, count) in left.chain(right_only) {
+            combined
+                .add(item.clone(), count)
+                .map_err(OutOfCapacityError::from)?;
+        }
+        Ok(combined)
+    }
+}
+
+impl<T, H, P> Clone for DynFixedCounter<T, H, P>
+where
+    T: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _counts: self._counts.clone(),
+            _total: self._total,
+        }
+    }
+}
+
+impl<T, H, P> fmt::Debug for DynFixedCounter<T, H, P>
+where
+    T: Hash + std::cmp::Eq + fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self._counts, f)
+    }
+}
+
+// Counters are equal when they hold the same counts, whatever their order or capacity.
+impl<T, H, P> PartialEq for DynFixedCounter<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self._counts == other._counts
+    }
+}
+
+impl<T, H, P> Eq for DynFixedCounter<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+// Panics once the counter is full, use try_extend to get the rejected item back instead.
+impl<T, H, P> Extend<T> for DynFixedCounter<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if let Err(error) = self.try_extend(iter) {
+            panic!("{}", error);
+        }
+    }
+}
+
+// &a - &b, &a | &b and &a & &b are difference, union and intersection. There is no + as Add
+// would shadow the add method on counters, use sum instead.
+macro_rules! impl_counter_operator {
+    ($op:ident, $fn:ident, $method:ident, $combine:expr) => {
+        impl<T, H, P> $op<&DynFixedCounter<T, H, P>> for &DynFixedCounter<T, H, P>
+        where
+            T: Hash + std::cmp::Eq + Clone,
+            H: Default + Hasher,
+            P: Probe,
+        {
+            type Output = Result<DynFixedCounter<T, H, P>, OutOfCapacityError>;
+
+            fn $fn(self, other: &DynFixedCounter<T, H, P>) -> Self::Output {
+                self.$method(other)
+            }
+        }
+
+        #[cfg(feature = "nightly")]
+        impl<T, const C: usize, const C2: usize, H, P> $op<&FixedSizeCounter<T, C2, H, P>>
+            for &FixedSizeCounter<T, C, H, P>
+        where
+            Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+            Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+            T: Hash + std::cmp::Eq + Clone,
+            H: Default + Hasher,
+            P: Probe,
+        {
+            type Output = Result<FixedSizeCounter<T, C, H, P>, OutOfCapacityError>;
+
+            fn $fn(self, other: &FixedSizeCounter<T, C2, H, P>) -> Self::Output {
+                Ok(FixedSizeCounter {
+                    _counter: self._combine(other, C, $combine)?,
+                })
+            }
+        }
+    };
+}
+
+impl_counter_operator!(Sub, sub, difference, u32::saturating_sub);
+impl_counter_operator!(BitOr, bitor, union, u32::max);
+impl_counter_operator!(BitAnd, bitand, intersection, u32::min);
+
+// Compile time checked capacity. All counter operations are provided by the DynFixedCounter it
+// dereferences to.
+#[cfg(feature = "nightly")]
+pub struct FixedSizeCounter<T, const C: usize, H = DefaultHasher, P = LinearProbe>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _counter: DynFixedCounter<T, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Default for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    const CAPACITY: usize = C;
+
+    pub fn placeholder() -> FixedSizeCounter<T, C, H, P> {
+        FixedSizeCounter {
+            _counter: DynFixedCounter::placeholder(C),
+        }
+    }
+
+    pub fn new() -> FixedSizeCounter<T, C, H, P> {
+        FixedSizeCounter {
+            _counter: DynFixedCounter::new(C),
+        }
+    }
+
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, InsertError<T, u32>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut counter = Self::new();
+        counter.try_extend(iter)?;
+        Ok(counter)
+    }
+
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Deref for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynFixedCounter<T, H, P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self._counter
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> DerefMut for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._counter
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Clone for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _counter: self._counter.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> fmt::Debug for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq + fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self._counter, f)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> PartialEq for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self._counter == other._counter
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Eq for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Extend<T> for FixedSizeCounter<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self._counter.extend(iter);
+    }
+}
+
@@ -16,4 +16,143 @@ impl fmt::Display for OutOfCapacityError {
     }
 }
 
-impl error::Error for OutOfCapacityError {}
\ No newline at end of file
+impl error::Error for OutOfCapacityError {}
+
+// Returned when an insert does not fit. Hands the rejected key and value back to the caller.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InsertError<K, V> {
+    pub capacity: usize,
+    pub key: K,
+    pub value: V,
+}
+
+impl<K, V> InsertError<K, V> {
+    pub fn into_inner(self) -> (K, V) {
+        (self.key, self.value)
+    }
+}
+
+impl<K, V> From<InsertError<K, V>> for OutOfCapacityError {
+    fn from(error: InsertError<K, V>) -> Self {
+        OutOfCapacityError {
+            capacity: error.capacity,
+        }
+    }
+}
+
+impl<K, V> fmt::Display for InsertError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "HashMap has reached its capacity of {} entries",
+            self.capacity
+        )
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> error::Error for InsertError<K, V> {}
+
+// Returned by graph inserts. `connection` is None when the node itself did not fit, otherwise
+// it is the position of the rejected connection. Connections before it were inserted, the ones
+// after it were not attempted and are handed back in `remaining`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphInsertError<K, V> {
+    pub connection: Option<usize>,
+    pub error: InsertError<K, V>,
+    pub remaining: Vec<(K, V)>,
+}
+
+impl<K, V> From<GraphInsertError<K, V>> for OutOfCapacityError {
+    fn from(error: GraphInsertError<K, V>) -> Self {
+        error.error.into()
+    }
+}
+
+impl<K, V> fmt::Display for GraphInsertError<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.connection {
+            Some(i) => write!(f, "{} (connection {})", self.error, i),
+            None => self.error.fmt(f),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> error::Error for GraphInsertError<K, V> {}
+
+// Returned by bimap inserts. Hands the rejected pair back. The conflicts say which side is
+// already paired with a different value on the other side.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BiMapInsertError<L, R> {
+    OutOfCapacity(InsertError<L, R>),
+    LeftConflict(L, R),
+    RightConflict(L, R),
+    BothConflict(L, R),
+}
+
+impl<L, R> BiMapInsertError<L, R> {
+    pub fn into_inner(self) -> (L, R) {
+        match self {
+            Self::OutOfCapacity(error) => error.into_inner(),
+            Self::LeftConflict(left, right)
+            | Self::RightConflict(left, right)
+            | Self::BothConflict(left, right) => (left, right),
+        }
+    }
+}
+
+impl<L, R> fmt::Display for BiMapInsertError<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfCapacity(error) => error.fmt(f),
+            Self::LeftConflict(..) => write!(f, "Left value is already paired with another value"),
+            Self::RightConflict(..) => {
+                write!(f, "Right value is already paired with another value")
+            }
+            Self::BothConflict(..) => {
+                write!(f, "Both values are already paired with other values")
+            }
+        }
+    }
+}
+
+impl<L: fmt::Debug, R: fmt::Debug> error::Error for BiMapInsertError<L, R> {}
+
+// Returned when a snapshot cannot be loaded into the requested collection.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    NotASnapshot,
+    UnsupportedVersion(u32),
+    WrongKind,
+    LayoutMismatch,
+    CapacityMismatch { expected: usize, found: usize },
+    HasherMismatch,
+    ProbeMismatch,
+    ChecksumMismatch,
+    Corrupt,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotASnapshot => write!(f, "Data does not start with a snapshot header"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "Snapshot version {} is not supported", version)
+            }
+            Self::WrongKind => write!(f, "Snapshot holds a different kind of collection"),
+            Self::LayoutMismatch => {
+                write!(f, "Snapshot layout does not match the key and value types")
+            }
+            Self::CapacityMismatch { expected, found } => write!(
+                f,
+                "Snapshot has a capacity of {} entries, expected {}",
+                found, expected
+            ),
+            Self::HasherMismatch => write!(f, "Snapshot was written with a different hasher"),
+            Self::ProbeMismatch => write!(f, "Snapshot was written with a different probe"),
+            Self::ChecksumMismatch => write!(f, "Snapshot checksum does not match its contents"),
+            Self::Corrupt => write!(f, "Snapshot is truncated or inconsistent"),
+        }
+    }
+}
+
+impl error::Error for SnapshotError {}
@@ -0,0 +1,176 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use crate::{check::prime_at_least, hash_map_internal};
+
+// Common interface of the bounded caches, so callers can switch between LRU, LFU and CLOCK
+// eviction without changing code.
+pub trait EvictionPolicy<K, V> {
+    // Inserts or updates an entry, evicting another one first when the cache is full.
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    // Looks an entry up and records the access.
+    fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized;
+
+    // Looks an entry up without recording the access.
+    fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized;
+
+    fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized;
+
+    fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized;
+
+    // Removes and returns the entry the policy would evict next.
+    fn evict(&mut self) -> Option<(K, V)>;
+
+    // Called with every entry that insert pushes out of the cache.
+    fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F);
+
+    fn capacity(&self) -> usize;
+
+    fn size(&self) -> usize;
+}
+
+// Body of the EvictionPolicy impls of the compile time sized caches, forwarding every method
+// to the runtime sized cache they wrap in `_cache`.
+#[cfg(feature = "nightly")]
+macro_rules! forward_eviction_policy {
+    () => {
+        fn insert(&mut self, key: K, value: V) -> Option<V> {
+            self._cache.insert(key, value)
+        }
+
+        fn get<Q>(&mut self, key: &Q) -> Option<&V>
+        where
+            K: std::borrow::Borrow<Q>,
+            Q: std::hash::Hash + std::cmp::Eq + ?Sized,
+        {
+            self._cache.get(key)
+        }
+
+        fn peek<Q>(&self, key: &Q) -> Option<&V>
+        where
+            K: std::borrow::Borrow<Q>,
+            Q: std::hash::Hash + std::cmp::Eq + ?Sized,
+        {
+            self._cache.peek(key)
+        }
+
+        fn remove<Q>(&mut self, key: &Q) -> Option<V>
+        where
+            K: std::borrow::Borrow<Q>,
+            Q: std::hash::Hash + std::cmp::Eq + ?Sized,
+        {
+            self._cache.remove(key)
+        }
+
+        fn exists<Q>(&self, key: &Q) -> bool
+        where
+            K: std::borrow::Borrow<Q>,
+            Q: std::hash::Hash + std::cmp::Eq + ?Sized,
+        {
+            self._cache.exists(key)
+        }
+
+        fn evict(&mut self) -> Option<(K, V)> {
+            self._cache.evict()
+        }
+
+        fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F) {
+            self._cache.set_eviction_callback(callback)
+        }
+
+        fn capacity(&self) -> usize {
+            self._cache.capacity()
+        }
+
+        fn size(&self) -> usize {
+            self._cache.size()
+        }
+    };
+}
+#[cfg(feature = "nightly")]
+pub(crate) use forward_eviction_policy;
+
+// The slot array is kept about a third larger than the number of cached entries, so a full cache
+// still has empty slots to end unsuccessful lookups and evictions rarely trigger a compaction.
+pub(crate) const fn cache_table_capacity(capacity: usize) -> usize {
+    prime_at_least(capacity + capacity / 3 + 1)
+}
+
+// Entry of the LFU and CLOCK caches. LFU keeps the access count in `_uses`, CLOCK only tells a
+// zero (not referenced since the hand last passed) from a non zero value.
+pub struct CacheEntry<K, V> {
+    _key: K,
+    _value: V,
+    _hash: u64,
+    _next: usize,
+    _prev: usize,
+    _uses: u32,
+}
+
+impl<K, V> CacheEntry<K, V> {
+    pub(crate) fn uses(&self) -> u32 {
+        self._uses
+    }
+
+    pub(crate) fn mut_uses(&mut self) -> &mut u32 {
+        &mut self._uses
+    }
+}
+
+impl<K, V> hash_map_internal::Entry<K, V> for CacheEntry<K, V> {
+    fn key(&self) -> &K {
+        &self._key
+    }
+    fn value(&self) -> &V {
+        &self._value
+    }
+    fn consume_self(self) -> V {
+        self._value
+    }
+    fn into_key_value(self) -> (K, V) {
+        (self._key, self._value)
+    }
+    fn mut_value(&mut self) -> &mut V {
+        &mut self._value
+    }
+    fn next(&self) -> usize {
+        self._next
+    }
+    fn mut_next(&mut self) -> &mut usize {
+        &mut self._next
+    }
+    fn prev(&self) -> usize {
+        self._prev
+    }
+    fn mut_prev(&mut self) -> &mut usize {
+        &mut self._prev
+    }
+    fn hash(&self) -> u64 {
+        self._hash
+    }
+    fn new(key: K, value: V, hash: u64) -> Self {
+        Self {
+            _key: key,
+            _value: value,
+            _hash: hash,
+            _next: 0,
+            _prev: 0,
+            _uses: 0,
+        }
+    }
+}
@@ -1,32 +1,43 @@
 #![allow(dead_code)]
 
 use crate::{
-    hash_map::{MapIter, FixedSizeHashMap},
-    hash_set::{FixedSizeHashSet},
+    counter::DynFixedCounter,
+    hash_map::MapIter,
+    hash_set::{DynFixedHashSet},
     hash_map_internal::{Entry, FixedSizeHashMapImpl},
-    OutOfCapacityError
+    probe::LinearProbe,
+    errors::{GraphInsertError, InsertError, SnapshotError},
+    snapshot::{self, KIND_GRAPH, Layout, SnapshotCodec},
 };
 
+use std::borrow::Borrow;
+use std::fmt;
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::mem;
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
 
+#[cfg(feature = "nightly")]
 use crate::check::{Check, IsTrue, is_prime_and_within_limit};
+use crate::check::prime_at_least;
 
 const MAX_EDGES: usize = 151;
 
-type OutEdges = FixedSizeHashMap<usize, u32, MAX_EDGES>;
-type InEdges = FixedSizeHashSet<usize, MAX_EDGES>;
+type OutEdges = DynFixedCounter<usize>;
+type InEdges = DynFixedHashSet<usize>;
 
-pub struct NodeEntry<K, V, const C: usize> {
+#[derive(Clone, Debug)]
+pub struct NodeEntry<K, V> {
     _key: K,
     _value: V,
+    _hash: u64,
     _next: usize,
     _prev: usize,
     _out_edges: OutEdges,
     _in_edges: InEdges,
 }
 
-impl<K, V, const C: usize> Entry<K, V, C> for NodeEntry<K, V, C> {
+impl<K, V> Entry<K, V> for NodeEntry<K, V> {
     fn key(&self) -> &K {
         &self._key
     }
@@ -36,6 +47,9 @@ impl<K, V, const C: usize> Entry<K, V, C> for NodeEntry<K, V, C> {
     fn consume_self(self) -> V {
         self._value
     }
+    fn into_key_value(self) -> (K, V) {
+        (self._key, self._value)
+    }
     fn mut_value(&mut self) -> &mut V {
         &mut self._value
     }
@@ -51,47 +65,66 @@ impl<K, V, const C: usize> Entry<K, V, C> for NodeEntry<K, V, C> {
     fn mut_prev(&mut self) -> &mut usize {
         &mut self._prev
     }
-    fn new(key: K, value: V) -> Self {
+    fn hash(&self) -> u64 {
+        self._hash
+    }
+    fn new(key: K, value: V, hash: u64) -> Self {
         Self {
             _key: key,
             _value: value,
-            _next: C,
-            _prev: C,
-            _in_edges: Default::default(),
-            _out_edges: Default::default(),
+            _hash: hash,
+            _next: 0,
+            _prev: 0,
+            _in_edges: InEdges::new(MAX_EDGES),
+            _out_edges: OutEdges::new(MAX_EDGES),
+        }
+    }
+    fn remap_indices(&mut self, new_index_of: &[usize]) {
+        let mut out_edges = OutEdges::new(MAX_EDGES);
+        for (to_index, weight) in self._out_edges.iter_head() {
+            let _ = out_edges.add(new_index_of[*to_index], *weight);
         }
+        self._out_edges = out_edges;
+
+        let mut in_edges = InEdges::new(MAX_EDGES);
+        for from_index in self._in_edges.iter_head() {
+            let _ = in_edges.insert(new_index_of[*from_index]);
+        }
+        self._in_edges = in_edges;
     }
 }
 
-pub struct Node<'a, K, V, const C: usize, H>
+pub struct Node<'a, K, V, H>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
 {
-    _node_entry: &'a NodeEntry<K, V, C>,
-    _graph: &'a FixedSizeHashGraphImpl<K, V, C, H>,
+    _node_entry: &'a NodeEntry<K, V>,
+    _graph: &'a DynFixedHashGraphImpl<K, V, H>,
 }
 
-impl<'a, K, V, const C: usize, H> Node<'a, K, V, C, H>
+impl<'a, K, V, H> Node<'a, K, V, H>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
 {
-    pub fn iter_out_edges(&self) -> EdgeIter<'_, K, V, C, H> {
+    pub fn iter_out_edges(&self) -> EdgeIter<'_, K, V, H> {
         EdgeIter {
             _inner_iter: self._node_entry._out_edges.iter_head(),
             _graph: self._graph,
         }
     }
 
-    pub fn out_edge_weight(&self, to_key: &K) -> u32 {
+    pub fn out_edge_weight<Q>(&self, to_key: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._graph
             ._hash_map
             .get_index_of(to_key)
             .map_or(0, |to_index| {
-                self._node_entry._out_edges.get(&to_index).map_or(0, |v| *v)
+                self._node_entry._out_edges.count(&to_index)
             })
     }
 
@@ -103,27 +136,25 @@ where
     }
 }
 
-pub struct EdgeIter<'a, K, V, const C: usize, H>
+pub struct EdgeIter<'a, K, V, H>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
 {
-    _inner_iter: MapIter<'a, usize, u32, MAX_EDGES>,
-    _graph: &'a FixedSizeHashGraphImpl<K, V, C, H>,
+    _inner_iter: MapIter<'a, usize, u32>,
+    _graph: &'a DynFixedHashGraphImpl<K, V, H>,
 }
 
-impl<'a, K, V, const C: usize, H> Iterator for EdgeIter<'a, K, V, C, H>
+impl<'a, K, V, H> Iterator for EdgeIter<'a, K, V, H>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
 {
-    type Item = (Node<'a, K, V, C, H>, u32);
+    type Item = (Node<'a, K, V, H>, u32);
     fn next(&mut self) -> Option<Self::Item> {
         self._inner_iter
             .next()
-            .map(|(edge_index, edge_weight)| {
+            .and_then(|(edge_index, edge_weight)| {
                 self._graph
                     ._hash_map
                     .get_entry_at(*edge_index)
@@ -137,7 +168,6 @@ where
                         )
                     })
             })
-            .flatten()
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -148,47 +178,81 @@ where
     }
 }
 
-pub struct FixedSizeHashGraphImpl<K, V, const C: usize, H>
+// Same graph as FixedSizeHashGraphImpl, but the node capacity is chosen when the graph is
+// constructed and is rounded up to the next prime. Needs no nightly features.
+pub struct DynFixedHashGraphImpl<K, V, H>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
 {
-    _hash_map: FixedSizeHashMapImpl<K, V, C, H, NodeEntry<K, V, C>>,
+    _hash_map: FixedSizeHashMapImpl<K, V, H, NodeEntry<K, V>, LinearProbe>,
     _empty_out_edges: OutEdges,
     _empty_in_edges: InEdges,
 }
 
-impl<'a, K: 'a, V: 'a, const C: usize, H> FixedSizeHashGraphImpl<K, V, C, H>
+impl<'a, K: 'a, V: 'a, H> DynFixedHashGraphImpl<K, V, H>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
 {
-    pub fn new() -> Self {
+    pub fn new(capacity: usize) -> Self {
         Self {
-            _hash_map: Default::default(),
-            _empty_in_edges: InEdges::placeholder(),
-            _empty_out_edges: OutEdges::placeholder()
+            _hash_map: FixedSizeHashMapImpl::new(prime_at_least(capacity)),
+            _empty_in_edges: InEdges::placeholder(MAX_EDGES),
+            _empty_out_edges: OutEdges::placeholder(MAX_EDGES)
+        }
+    }
+
+    // Fallible counterpart of collect(): fails with the first node or connection that does not
+    // fit.
+    pub fn try_from_iter<I>(capacity: usize, iter: I) -> Result<Self, GraphInsertError<K, V>>
+    where
+        I: IntoIterator<Item = ((K, V), Vec<(K, V)>)>,
+    {
+        let mut graph = Self::new(capacity);
+        graph.try_extend(iter)?;
+        Ok(graph)
+    }
+
+    // Inserts nodes with their connections until one does not fit. Everything before it stays
+    // inserted, the nodes after it are not consumed.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), GraphInsertError<K, V>>
+    where
+        I: IntoIterator<Item = ((K, V), Vec<(K, V)>)>,
+    {
+        for (key_value, connections) in iter {
+            self.insert(key_value, connections)?;
         }
+        Ok(())
     }
 
     pub fn insert(
         &'a mut self,
         key_value: (K, V),
         connections: Vec<(K, V)>,
-    ) -> Result<(), OutOfCapacityError> {
-        let Ok((index, _)) = self._hash_map.insert_get_index(key_value.0, key_value.1) else {
-            return Result::Err(OutOfCapacityError {
-                capacity: self._hash_map.capacity(),
-            });
+    ) -> Result<(), GraphInsertError<K, V>> {
+        let index = match self._hash_map.insert_get_index(key_value.0, key_value.1) {
+            Ok((index, _)) => index,
+            Err(error) => {
+                return Result::Err(GraphInsertError {
+                    connection: None,
+                    error,
+                    remaining: connections,
+                });
+            }
         };
 
-        for (to_key, to_value) in connections {
-            let Ok((to_index, _)) = self._hash_map.insert_get_index(to_key, to_value) else {
-                return Result::Err(OutOfCapacityError {
-                    capacity: self._hash_map.capacity(),
-                });
+        let mut connections = connections.into_iter().enumerate();
+        while let Some((i, (to_key, to_value))) = connections.next() {
+            let to_index = match self._hash_map.insert_get_index(to_key, to_value) {
+                Ok((to_index, _)) => to_index,
+                Err(error) => {
+                    return Result::Err(GraphInsertError {
+                        connection: Some(i),
+                        error,
+                        remaining: connections.map(|(_, connection)| connection).collect(),
+                    });
+                }
             };
 
             if index == to_index {
@@ -196,14 +260,7 @@ where
             }
 
             if let Some(node) = self._hash_map.get_mut_entry_at(index) {
-                match node._out_edges.get_mut(&to_index) {
-                    Some(edge_weight) => {
-                        *edge_weight += 1;
-                    }
-                    None => {
-                        let _ = node._out_edges.insert(to_index, 1);
-                    }
-                }
+                let _ = node._out_edges.add(to_index, 1);
             }
 
             if let Some(to_node) = self._hash_map.get_mut_entry_at(to_index) {
@@ -211,10 +268,14 @@ where
             }
         }
 
-        return Ok(());
+        Ok(())
     }
 
-    pub fn connect_to(&mut self, k: &K, to_keys: Vec<&K>) {
+    pub fn connect_to<Q>(&mut self, k: &Q, to_keys: Vec<&Q>)
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         let Some(index) = self._hash_map.get_index_of(k) else {
             return;
         };
@@ -225,9 +286,7 @@ where
             };
             if index != to_index {
                 if let Some(node) = self._hash_map.get_mut_entry_at(index) {
-                    let _ = node
-                        ._out_edges
-                        .insert_or(to_index, 1, |weight| *weight += 1);
+                    let _ = node._out_edges.add(to_index, 1);
                 }
                 if let Some(to_node) = self._hash_map.get_mut_entry_at(to_index) {
                     let _ = to_node._in_edges.insert(index);
@@ -236,16 +295,61 @@ where
         }
     }
 
-    pub fn remove(&mut self, key: &K) {
-        let (index, edges, from_nodes): (usize, OutEdges, InEdges) =
-            match self._hash_map.get_mut_entry_and_index_of(key) {
-                Some((node, index)) => (
-                    index,
-                    mem::take(&mut node._out_edges),
-                    mem::take(&mut node._in_edges),
-                ),
-                None => return,
-            };
+    // Inserts a node without connections and returns its slot.
+    pub(crate) fn insert_node(&mut self, key: K, value: V) -> Result<usize, InsertError<K, V>> {
+        self._hash_map.insert_get_index(key, value).map(|(index, _)| index)
+    }
+
+    // Adds weight to the edge from the node at index to an existing node. False when the target
+    // is missing, is the node itself, or the edge does not fit.
+    pub(crate) fn connect_at_with_weight<Q>(&mut self, index: usize, to_key: &Q, weight: u32) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(to_index) = self._hash_map.get_index_of(to_key) else {
+            return false;
+        };
+        if index == to_index {
+            return false;
+        }
+
+        let connected = self._hash_map.get_mut_entry_at(index).is_some_and(|node| {
+            node._out_edges.add(to_index, weight).is_ok()
+        });
+        connected
+            && self
+                ._hash_map
+                .get_mut_entry_at(to_index)
+                .is_some_and(|to_node| to_node._in_edges.insert(index).is_ok())
+    }
+
+    pub(crate) fn iter_nodes(&self) -> impl Iterator<Item = Node<'_, K, V, H>> {
+        self._hash_map.iter_head().map(|node_entry| Node {
+            _node_entry: node_entry,
+            _graph: self,
+        })
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(index) = self._hash_map.get_index_of(key) else {
+            return;
+        };
+        self._detach(index);
+        self._hash_map.remove_at(index);
+    }
+
+    // Drops every edge into and out of the node at index. The node itself stays in place.
+    fn _detach(&mut self, index: usize) {
+        let Some(node) = self._hash_map.get_mut_entry_at(index) else {
+            return;
+        };
+        let edges = mem::replace(&mut node._out_edges, OutEdges::placeholder(MAX_EDGES));
+        let from_nodes = mem::replace(&mut node._in_edges, InEdges::placeholder(MAX_EDGES));
 
         for (edge_index, _) in edges.iter_head() {
             if let Some(to_node) = self._hash_map.get_mut_entry_at(*edge_index) {
@@ -258,80 +362,509 @@ where
                 from_node._out_edges.remove(&index);
             };
         }
+    }
+
+    // Removes every node keep rejects, together with its edges, in one walk of the node list.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut index = self._hash_map.head_index();
+        while let Some(node) = self._hash_map.get_mut_entry_at(index) {
+            let next = node.next();
+            if !keep(&node._key, &mut node._value) {
+                self._detach(index);
+                self._hash_map.take_at(index);
+            }
+            index = next;
+        }
+        if self._hash_map.needs_compaction() {
+            self._hash_map.compact();
+        }
+    }
+
+    // Lazily removes and yields the nodes pred picks, together with their edges. Nodes not
+    // reached before the iterator is dropped stay in the graph.
+    pub fn extract_if<F>(&mut self, pred: F) -> GraphExtractIf<'_, K, V, H, F>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        GraphExtractIf {
+            _next: self._hash_map.head_index(),
+            _graph: self,
+            _pred: pred,
+        }
+    }
 
-        self._hash_map.remove(&key);
+    pub fn clear(&mut self) {
+        self._hash_map.clear();
     }
 
-    pub fn disconnect_from(&mut self, key: &K, to_keys: Vec<&K>) {
+    pub fn disconnect_from<Q>(&mut self, key: &Q, to_keys: Vec<&Q>)
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         let Some(index) = self._hash_map.get_index_of(key) else {
             return;
         };
 
         for to_key in to_keys {
-            if let Some(to_index) = self._hash_map.get_index_of(to_key) {
-                if let Some(node) = self._hash_map.get_mut_entry_at(index) {
-                    if let Some(edge_weight) = node._out_edges.get_mut(&to_index) {
-                        if *edge_weight == 1 {
-                            node._out_edges.remove(&to_index);
-                            if let Some(to_node) = self._hash_map.get_mut_entry_at(to_index) {
-                                to_node._in_edges.remove(&index);
-                            }
-                        } else {
-                            *edge_weight -= 1;
-                        }
-                    }
-                }
+            if let Some(to_index) = self._hash_map.get_index_of(to_key)
+                && let Some(node) = self._hash_map.get_mut_entry_at(index)
+                && node._out_edges.exists(&to_index)
+                && node._out_edges.subtract(&to_index, 1) == 0
+                && let Some(to_node) = self._hash_map.get_mut_entry_at(to_index)
+            {
+                to_node._in_edges.remove(&index);
             }
         }
     }
 
-    pub fn disconnect_all(&mut self, key: &K) {
+    pub fn disconnect_all<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         let Some((node, index)) = self._hash_map.get_mut_entry_and_index_of(key) else {
             return;
         };
 
-        for (to_index, _) in mem::take(&mut node._out_edges).iter_head() {
+        for (to_index, _) in mem::replace(&mut node._out_edges, OutEdges::new(MAX_EDGES)).iter_head() {
             if let Some(to_node) = self._hash_map.get_mut_entry_at(*to_index) {
                 to_node._in_edges.remove(&index);
             }
         }
     }
 
-    pub fn node(&self, key: &K) -> Option<Node<'_, K, V, C, H>> {
+    // Moves every entry into a table of at least the given capacity, keeping the insertion
+    // order. Never shrinks the graph. Not public for the same reason as DynFixedHashMap::grow.
+    pub(crate) fn grow(&mut self, capacity: usize) {
+        let capacity = prime_at_least(capacity);
+        if capacity > self.capacity() {
+            self._hash_map.grow(capacity);
+        }
+    }
+
+    pub fn compact(&mut self) {
+        self._hash_map.compact()
+    }
+
+    pub fn tombstone_count(&self) -> usize {
+        self._hash_map.tombstone_count()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._hash_map.capacity()
+    }
+
+    pub fn size(&self) -> usize {
+        self._hash_map.size()
+    }
+
+    pub fn node<Q>(&self, key: &Q) -> Option<Node<'_, K, V, H>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._hash_map
             .get_entry_and_index_of(key)
             .map(|(e, _)| Node {
                 _node_entry: e,
-                _graph: &self,
+                _graph: self,
             })
     }
 
-    pub fn iter_out_edges(&self, k: &K) -> EdgeIter<'_, K, V, C, H> {
+    pub fn iter_out_edges<Q>(&self, k: &Q) -> EdgeIter<'_, K, V, H>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         if let Some((node_entry, _)) = self._hash_map.get_entry_and_index_of(k) {
             EdgeIter {
                 _inner_iter: node_entry._out_edges.iter_head(),
-                _graph: &self,
+                _graph: self,
             }     
         } else {
             EdgeIter {
                 _inner_iter: self._empty_out_edges.iter_head(),
-                _graph: &self,
+                _graph: self,
             }       
         }
     }
 
-    pub fn out_edge_weight(&self, from_key: &K, to_key: &K) -> u32 {
+    pub fn out_edge_weight<Q>(&self, from_key: &Q, to_key: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._hash_map.get_entry_and_index_of(from_key)
             .map_or(0, |(node_entry, _)| {
                 self._hash_map.get_index_of(to_key).map_or(
-                    0, |to_index| node_entry._out_edges.get(&to_index).map_or(
-                        0, |weight| *weight
-                    )
+                    0, |to_index| node_entry._out_edges.count(&to_index)
                 )
             })
     }
 
 }
 
+// Binary snapshots, see the snapshot module for the format. Each node record carries its out
+// edges as (slot, weight) pairs and its in edges as slots.
+impl<K, V, H> DynFixedHashGraphImpl<K, V, H>
+where
+    K: Hash + std::cmp::Eq + SnapshotCodec,
+    V: SnapshotCodec,
+    H: Default + Hasher,
+{
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        snapshot::write_table(&self._hash_map, KIND_GRAPH, Layout::Packed, |node, out| {
+            node._key.encode(out);
+            node._value.encode(out);
+            snapshot::encode_index(out, node._out_edges.size());
+            for (to_index, weight) in node._out_edges.iter_head() {
+                snapshot::encode_index(out, *to_index);
+                weight.encode(out);
+            }
+            snapshot::encode_index(out, node._in_edges.size());
+            for from_index in node._in_edges.iter_head() {
+                snapshot::encode_index(out, *from_index);
+            }
+        })
+    }
+
+    // The graph gets the capacity it was written with.
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        Self::_from_snapshot(bytes, None)
+    }
+
+    fn _from_snapshot(bytes: &[u8], capacity: Option<usize>) -> Result<Self, SnapshotError> {
+        let hash_map = snapshot::read_table(bytes, KIND_GRAPH, capacity, |input, hash| {
+            let mut node = NodeEntry::new(K::decode(input)?, V::decode(input)?, hash);
+            for _ in 0..snapshot::decode_index(input)? {
+                let to_index = snapshot::decode_index(input)?;
+                node._out_edges.add(to_index, u32::decode(input)?).ok()?;
+            }
+            for _ in 0..snapshot::decode_index(input)? {
+                node._in_edges.insert(snapshot::decode_index(input)?).ok()?;
+            }
+            Some(node)
+        })?;
+
+        let edges_are_valid = hash_map.iter_head().all(|node| {
+            node._out_edges.iter_head().all(|(i, _)| hash_map.get_entry_at(*i).is_some())
+                && node._in_edges.iter_head().all(|i| hash_map.get_entry_at(*i).is_some())
+        });
+        if !edges_are_valid {
+            return Err(SnapshotError::Corrupt);
+        }
+
+        Ok(Self {
+            _hash_map: hash_map,
+            _empty_out_edges: OutEdges::placeholder(MAX_EDGES),
+            _empty_in_edges: InEdges::placeholder(MAX_EDGES),
+        })
+    }
+}
+
+impl<K, V, H> Clone for DynFixedHashGraphImpl<K, V, H>
+where
+    K: Hash + std::cmp::Eq + Clone,
+    V: Clone,
+    H: Default + Hasher,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _hash_map: self._hash_map.clone(),
+            _empty_out_edges: OutEdges::placeholder(MAX_EDGES),
+            _empty_in_edges: InEdges::placeholder(MAX_EDGES),
+        }
+    }
+}
+
+// Shows every node in insertion order as key: (value, [(to_key, weight), ...]).
+impl<K, V, H> fmt::Debug for DynFixedHashGraphImpl<K, V, H>
+where
+    K: Hash + std::cmp::Eq + fmt::Debug,
+    V: fmt::Debug,
+    H: Default + Hasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self._hash_map.iter_head().map(|node| {
+                let edges = node
+                    ._out_edges
+                    .iter_head()
+                    .filter_map(|(to_index, weight)| {
+                        self._hash_map.get_entry_at(*to_index).map(|to| (to.key(), *weight))
+                    })
+                    .collect::<Vec<_>>();
+                (node.key(), (node.value(), edges))
+            }))
+            .finish()
+    }
+}
+
+// Graphs are equal when they hold the same nodes and edge weights, whatever their order or
+// capacity.
+impl<K, V, H> PartialEq for DynFixedHashGraphImpl<K, V, H>
+where
+    K: Hash + std::cmp::Eq,
+    V: PartialEq,
+    H: Default + Hasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size()
+            && self._hash_map.iter_head().all(|node| {
+                other
+                    ._hash_map
+                    .get_entry_and_index_of(node.key())
+                    .is_some_and(|(other_node, _)| {
+                        node.value() == other_node.value()
+                            && node._out_edges.size() == other_node._out_edges.size()
+                            && node._out_edges.iter_head().all(|(to_index, weight)| {
+                                self._hash_map.get_entry_at(*to_index).is_some_and(|to| {
+                                    other.out_edge_weight(node.key(), to.key()) == *weight
+                                })
+                            })
+                    })
+            })
+    }
+}
+
+impl<K, V, H> Eq for DynFixedHashGraphImpl<K, V, H>
+where
+    K: Hash + std::cmp::Eq,
+    V: Eq,
+    H: Default + Hasher,
+{
+}
+
+// Panics once the graph is full, use try_extend to get the rejected node back instead.
+impl<K, V, H> Extend<((K, V), Vec<(K, V)>)> for DynFixedHashGraphImpl<K, V, H>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    fn extend<I: IntoIterator<Item = ((K, V), Vec<(K, V)>)>>(&mut self, iter: I) {
+        if let Err(error) = self.try_extend(iter) {
+            panic!("{}", error);
+        }
+    }
+}
+
+pub struct GraphExtractIf<'a, K, V, H, F>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    _graph: &'a mut DynFixedHashGraphImpl<K, V, H>,
+    _next: usize,
+    _pred: F,
+}
+
+impl<'a, K, V, H, F> Iterator for GraphExtractIf<'a, K, V, H, F>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self._graph._hash_map.get_mut_entry_at(self._next) {
+            let index = self._next;
+            self._next = node.next();
+            if (self._pred)(&node._key, &mut node._value) {
+                self._graph._detach(index);
+                return self._graph._hash_map.take_at(index).map(|node| node.into_key_value());
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self._graph.size()))
+    }
+}
+
+impl<'a, K, V, H, F> Drop for GraphExtractIf<'a, K, V, H, F>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    fn drop(&mut self) {
+        if self._graph._hash_map.needs_compaction() {
+            self._graph._hash_map.compact();
+        }
+    }
+}
+
+pub type DynFixedHashGraphMap<K, V> = DynFixedHashGraphImpl<K, V, DefaultHasher>;
+
+// Compile time checked capacity. All graph operations are provided by the DynFixedHashGraphImpl
+// it dereferences to.
+#[cfg(feature = "nightly")]
+pub struct FixedSizeHashGraphImpl<K, V, const C: usize, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    _graph: DynFixedHashGraphImpl<K, V, H>,
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Default for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    pub fn new() -> Self {
+        Self {
+            _graph: DynFixedHashGraphImpl::new(C),
+        }
+    }
+
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, GraphInsertError<K, V>>
+    where
+        I: IntoIterator<Item = ((K, V), Vec<(K, V)>)>,
+    {
+        Ok(Self {
+            _graph: DynFixedHashGraphImpl::try_from_iter(C, iter)?,
+        })
+    }
+
+    // Fails with CapacityMismatch unless the snapshot was written by a graph of capacity C.
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError>
+    where
+        K: SnapshotCodec,
+        V: SnapshotCodec,
+    {
+        Ok(Self {
+            _graph: DynFixedHashGraphImpl::_from_snapshot(bytes, Some(C))?,
+        })
+    }
+
+    pub const fn capacity(&self) -> usize {
+        C
+    }
+
+    pub fn grow_into<const C2: usize>(self) -> FixedSizeHashGraphImpl<K, V, C2, H>
+    where
+        Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+        Check<{ C2 >= C }>: IsTrue,
+    {
+        let mut graph = self._graph;
+        graph.grow(C2);
+        FixedSizeHashGraphImpl {
+            _graph: graph,
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Deref for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    type Target = DynFixedHashGraphImpl<K, V, H>;
+    fn deref(&self) -> &Self::Target {
+        &self._graph
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> DerefMut for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._graph
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Clone for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Clone,
+    V: Clone,
+    H: Default + Hasher,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _graph: self._graph.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> fmt::Debug for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + fmt::Debug,
+    V: fmt::Debug,
+    H: Default + Hasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self._graph.fmt(f)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> PartialEq for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    V: PartialEq,
+    H: Default + Hasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self._graph == other._graph
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Eq for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    V: Eq,
+    H: Default + Hasher,
+{
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Extend<((K, V), Vec<(K, V)>)> for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    fn extend<I: IntoIterator<Item = ((K, V), Vec<(K, V)>)>>(&mut self, iter: I) {
+        self._graph.extend(iter);
+    }
+}
+
+#[cfg(feature = "nightly")]
 pub type FixedSizeHashGraphMap<K, V, const C: usize> =
     FixedSizeHashGraphImpl<K, V, C, DefaultHasher>;
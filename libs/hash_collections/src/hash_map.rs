@@ -1,22 +1,38 @@
 #![allow(dead_code)]
 
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::iter::FusedIterator;
+use std::mem;
 use std::ops::{Index, IndexMut};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
 
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_prime_and_within_limit};
 use crate::{
-    check::{Check, IsTrue, is_prime_and_within_limit},
-    hash_map_internal::{Entry, FixedSizeHashMapImpl, MapIteratorImpl},
-    OutOfCapacityError
+    check::prime_at_least,
+    hash_map_internal::{
+        self, DrainImpl, Entry as _, ExtractIfImpl, FixedSizeHashMapImpl, IntoIteratorImpl,
+        MapIteratorImpl, MapIteratorMutImpl, SlotLookup,
+    },
+    probe::{LinearProbe, Probe},
+    errors::{InsertError, SnapshotError},
+    snapshot::{self, KIND_MAP, Layout, Pod, SnapshotCodec},
 };
 
-pub struct MapEntry<K, V, const C: usize> {
+#[derive(Clone, Debug)]
+pub struct MapEntry<K, V> {
     _key: K,
     _value: V,
+    _hash: u64,
     _next: usize,
     _prev: usize,
 }
 
-impl<K, V, const C: usize> Entry<K, V, C> for MapEntry<K, V, C> {
+impl<K, V> hash_map_internal::Entry<K, V> for MapEntry<K, V> {
     fn key(&self) -> &K {
         &self._key
     }
@@ -26,6 +42,9 @@ impl<K, V, const C: usize> Entry<K, V, C> for MapEntry<K, V, C> {
     fn consume_self(self) -> V {
         self._value
     }
+    fn into_key_value(self) -> (K, V) {
+        (self._key, self._value)
+    }
     fn mut_value(&mut self) -> &mut V {
         &mut self._value
     }
@@ -41,100 +60,316 @@ impl<K, V, const C: usize> Entry<K, V, C> for MapEntry<K, V, C> {
     fn mut_prev(&mut self) -> &mut usize {
         &mut self._prev
     }
-    fn new(key: K, value: V) -> Self {
+    fn hash(&self) -> u64 {
+        self._hash
+    }
+    fn new(key: K, value: V, hash: u64) -> Self {
         Self {
             _key: key,
             _value: value,
-            _next: C,
-            _prev: C,
+            _hash: hash,
+            _next: 0,
+            _prev: 0,
         }
     }
 }
 
-pub struct FixedSizeHashMap<K, V, const C: usize, H = DefaultHasher>
-where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
-    K: Hash + std::cmp::Eq,
-    H: Default + Hasher,
-{
-    _hash_map_internal: FixedSizeHashMapImpl<K, V, C, H, MapEntry<K, V, C>>,
+impl<K, V> MapEntry<K, V> {
+    pub(crate) fn key_and_mut_value(&mut self) -> (&K, &mut V) {
+        (&self._key, &mut self._value)
+    }
+
+    // Only for keys equal to the stored one, so the hash and slot stay valid.
+    pub(crate) fn replace_key(&mut self, key: K) -> K {
+        mem::replace(&mut self._key, key)
+    }
 }
 
-impl<K, V, const C: usize, H> Default for FixedSizeHashMap<K, V, C, H>
+pub(crate) type MapImpl<K, V, H, P> = FixedSizeHashMapImpl<K, V, H, MapEntry<K, V>, P>;
+
+// Same table as FixedSizeHashMap, but the capacity is chosen when the map is constructed and is
+// rounded up to the next prime. Needs no nightly features.
+pub struct DynFixedHashMap<K, V, H = DefaultHasher, P = LinearProbe>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
+    P: Probe,
 {
-    fn default() -> Self {
-        Self::new()
-    }
+    _hash_map_internal: MapImpl<K, V, H, P>,
 }
 
-impl<K, V, const C: usize, H> FixedSizeHashMap<K, V, C, H>
+impl<K, V, H, P> DynFixedHashMap<K, V, H, P>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
+    P: Probe,
 {
-    const CAPACITY: usize = C;
-    type _Hash = H;
-    
-    pub fn placeholder() -> FixedSizeHashMap<K, V, C, H> {
-        FixedSizeHashMap::<K, V, C, H> {
-            _hash_map_internal: FixedSizeHashMapImpl::placeholder(),
+    pub fn placeholder(capacity: usize) -> DynFixedHashMap<K, V, H, P> {
+        DynFixedHashMap::<K, V, H, P> {
+            _hash_map_internal: FixedSizeHashMapImpl::placeholder(prime_at_least(capacity)),
+        }
+    }
+
+    pub fn new(capacity: usize) -> DynFixedHashMap<K, V, H, P> {
+        DynFixedHashMap::<K, V, H, P> {
+            _hash_map_internal: FixedSizeHashMapImpl::new(prime_at_least(capacity)),
         }
     }
 
-    pub fn new() -> FixedSizeHashMap<K, V, C, H> {
-        FixedSizeHashMap::<K, V, C, H> {
-            _hash_map_internal: FixedSizeHashMapImpl::new(),
+    // Fallible counterpart of collect(): fails with the first entry that does not fit.
+    pub fn try_from_iter<I>(capacity: usize, iter: I) -> Result<Self, InsertError<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = Self::new(capacity);
+        map.try_extend(iter)?;
+        Ok(map)
+    }
+
+    // Inserts entries until one does not fit. Entries before it stay inserted, the ones after
+    // it are not consumed.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), InsertError<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in iter {
+            self.insert(key, value)?;
         }
+        Ok(())
     }
 
-    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, OutOfCapacityError> {
+    // Overwriting an existing key moves it to the back, so the list is in last-write order. Same as
+    // upsert_to_back.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, InsertError<K, V>> {
         let result = self._hash_map_internal.insert_get_index(key, value);
         result.map(|rv| rv.1)
     }
 
+    // Overwriting an existing key keeps its position, so the list is in first-insertion order.
+    pub fn insert_keep_order(&mut self, key: K, value: V) -> Result<Option<V>, InsertError<K, V>> {
+        let result = self._hash_map_internal.upsert_get_index(key, value, false);
+        result.map(|rv| rv.1)
+    }
+
+    pub fn upsert_to_back(&mut self, key: K, value: V) -> Result<Option<V>, InsertError<K, V>> {
+        let result = self._hash_map_internal.upsert_get_index(key, value, true);
+        result.map(|rv| rv.1)
+    }
+
     pub fn insert_or<F: FnOnce(&mut V)>(
         &mut self,
         key: K,
         value: V,
         op: F,
-    ) -> Result<(), OutOfCapacityError> {
-        if let Some(value) = self.get_mut(&key) {
-            op(value);
-            Ok(())
-        } else {
-            self.insert(key, value).map(|_| ())
-        }
+    ) -> Result<(), InsertError<K, V>> {
+        self.entry(key).and_modify(op).or_insert(value).map(|_| ())
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, H, P> {
+        Entry::from_map(&mut self._hash_map_internal, key)
     }
 
-    pub fn exists(&self, key: &K) -> bool {
+    pub fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._hash_map_internal.exists(key)
     }
 
-    pub fn get(&self, key: &K) -> Option<&V> {
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._hash_map_internal
             .get_entry_and_index_of(key)
             .map(|e| e.0.value())
     }
 
-    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._hash_map_internal
             .get_mut_entry_and_index_of(key)
             .map(|e| e.0.mut_value())
     }
 
-    pub fn remove(&mut self, key: &K) -> Option<V> {
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._hash_map_internal
             .remove(key)
             .map(|e| e.consume_self())
     }
 
-    pub const fn capacity(&self) -> usize {
-        Self::CAPACITY
+    // Removes the entry and moves the last entry into its position, like indexmap. O(1).
+    pub fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(key)?;
+        self._hash_map_internal
+            .swap_remove_at(index)
+            .map(|e| e.consume_self())
+    }
+
+    // Removes the entry, keeping the order of the rest. Same as remove, and O(1) unlike
+    // indexmap's.
+    pub fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.remove(key)
+    }
+
+    // Positions count from the head. Reaching position n walks the list from the nearer end.
+    pub fn get_index(&self, n: usize) -> Option<(&K, &V)> {
+        let index = self._hash_map_internal.index_at_rank(n)?;
+        self._hash_map_internal
+            .get_entry_at(index)
+            .map(|e| (e.key(), e.value()))
+    }
+
+    pub fn get_index_mut(&mut self, n: usize) -> Option<(&K, &mut V)> {
+        let index = self._hash_map_internal.index_at_rank(n)?;
+        self._hash_map_internal
+            .get_mut_entry_at(index)
+            .map(|e| e.key_and_mut_value())
+    }
+
+    pub fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(key)?;
+        self._hash_map_internal.rank_of(index)
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.head()
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.tail()
+    }
+
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        self._hash_map_internal
+            .pop_head()
+            .map(|e| e.into_key_value())
+    }
+
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        self._hash_map_internal
+            .pop_tail()
+            .map(|e| e.into_key_value())
+    }
+
+    // The move_* methods only relink the list and return false when a key is missing.
+    pub fn move_to_front<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(index) = self._hash_map_internal.get_index_of(key) else {
+            return false;
+        };
+        self._hash_map_internal.move_to_front(index);
+        true
+    }
+
+    pub fn move_to_back<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(index) = self._hash_map_internal.get_index_of(key) else {
+            return false;
+        };
+        self._hash_map_internal.move_to_back(index);
+        true
+    }
+
+    pub fn move_before<Q>(&mut self, key: &Q, anchor: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let (Some(index), Some(anchor_index)) = (
+            self._hash_map_internal.get_index_of(key),
+            self._hash_map_internal.get_index_of(anchor),
+        ) else {
+            return false;
+        };
+        self._hash_map_internal.move_before(index, anchor_index);
+        true
+    }
+
+    // Inserts or updates the entry and places it right after anchor. Without the anchor the entry
+    // ends up at the back, like insert.
+    pub fn insert_after<Q>(
+        &mut self,
+        anchor: &Q,
+        key: K,
+        value: V,
+    ) -> Result<Option<V>, InsertError<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let (index, old_value) = self._hash_map_internal.insert_get_index(key, value)?;
+        // Looked up after inserting, Robin Hood placement may have moved the anchor.
+        if let Some(anchor_index) = self._hash_map_internal.get_index_of(anchor) {
+            self._hash_map_internal.move_after(index, anchor_index);
+        }
+        Ok(old_value)
+    }
+
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        self._hash_map_internal
+            .sort_by(|a, b| compare(a.key(), a.value(), b.key(), b.value()));
+    }
+
+    pub fn sort_by_key<T, F>(&mut self, mut sort_key: F)
+    where
+        T: Ord,
+        F: FnMut(&K, &V) -> T,
+    {
+        self._hash_map_internal
+            .sort_by(|a, b| sort_key(a.key(), a.value()).cmp(&sort_key(b.key(), b.value())));
+    }
+
+    // Moves every entry into a table of at least the given capacity, keeping the insertion
+    // order. Never shrinks the map. Crate-private because the fixed-size wrapper derefs to this
+    // type; callers grow through grow_into so the const capacity stays in sync.
+    pub(crate) fn grow(&mut self, capacity: usize) {
+        let capacity = prime_at_least(capacity);
+        if capacity > self.capacity() {
+            self._hash_map_internal.grow(capacity);
+        }
+    }
+
+    pub fn compact(&mut self) {
+        self._hash_map_internal.compact()
+    }
+
+    pub fn tombstone_count(&self) -> usize {
+        self._hash_map_internal.tombstone_count()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._hash_map_internal.capacity()
     }
 
     pub fn size(&self) -> usize {
@@ -149,58 +384,904 @@ where
         self._hash_map_internal.tail().map(|e| (e.key(), e.value()))
     }
 
-    pub fn iter_head(&self) -> MapIter<'_, K, V, C> {
+    pub fn iter_head(&self) -> MapIter<'_, K, V> {
         MapIter {
             _inner_iter: self._hash_map_internal.iter_head(),
         }
     }
 
-    pub fn iter_tail(&self) -> MapIter<'_, K, V, C> {
+    pub fn iter_tail(&self) -> MapIter<'_, K, V> {
         MapIter {
             _inner_iter: self._hash_map_internal.iter_tail(),
         }
     }
+
+    pub fn iter_mut(&mut self) -> MapIterMut<'_, K, V> {
+        MapIterMut {
+            _inner_iter: self._hash_map_internal.iter_head_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> MapKeys<'_, K, V> {
+        MapKeys {
+            _inner_iter: self._hash_map_internal.iter_head(),
+        }
+    }
+
+    pub fn values(&self) -> MapValues<'_, K, V> {
+        MapValues {
+            _inner_iter: self._hash_map_internal.iter_head(),
+        }
+    }
+
+    pub fn values_mut(&mut self) -> MapValuesMut<'_, K, V> {
+        MapValuesMut {
+            _inner_iter: self._hash_map_internal.iter_head_mut(),
+        }
+    }
+
+    // Removes every entry, yielding them from head to tail. The map is empty even if the
+    // drain is dropped early.
+    pub fn drain(&mut self) -> MapDrain<'_, K, V> {
+        MapDrain {
+            _inner_iter: self._hash_map_internal.drain(),
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self._hash_map_internal.retain(|e| {
+            let (key, value) = e.key_and_mut_value();
+            keep(key, value)
+        });
+    }
+
+    // Lazily removes and yields the entries pred picks, in list order. Entries not reached
+    // before the iterator is dropped stay in the map.
+    pub fn extract_if<F>(&mut self, pred: F) -> MapExtractIf<'_, K, V, H, P, F>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        MapExtractIf {
+            _inner_iter: self._hash_map_internal.extract_if(),
+            _pred: pred,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self._hash_map_internal.clear();
+    }
 }
 
-impl<'a, K: 'a, V: 'a, const C: usize, H> Index<&K> for FixedSizeHashMap<K, V, C, H>
+// Binary snapshots, see the snapshot module for the format.
+impl<K, V, H, P> DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + SnapshotCodec,
+    V: SnapshotCodec,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn to_snapshot(&self) -> Vec<u8> {
+        snapshot::write_table(&self._hash_map_internal, KIND_MAP, Layout::Packed, |e, out| {
+            e.key().encode(out);
+            e.value().encode(out);
+        })
+    }
+
+    // Loads either layout. The map gets the capacity it was written with.
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        Self::_from_snapshot(bytes, None)
+    }
+
+    fn _from_snapshot(bytes: &[u8], capacity: Option<usize>) -> Result<Self, SnapshotError> {
+        let map = snapshot::read_table(bytes, KIND_MAP, capacity, |input, hash| {
+            Some(MapEntry::new(K::decode(input)?, V::decode(input)?, hash))
+        })?;
+        Ok(Self {
+            _hash_map_internal: map,
+        })
+    }
+}
+
+impl<K, V, H, P> DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + Pod,
+    V: Pod,
+    H: Default + Hasher,
+    P: Probe,
+{
+    // Fixed layout snapshot that SnapshotView can read in place.
+    pub fn to_pod_snapshot(&self) -> Vec<u8> {
+        let layout = Layout::Fixed {
+            key_size: mem::size_of::<K>(),
+            value_size: mem::size_of::<V>(),
+        };
+        snapshot::write_table(&self._hash_map_internal, KIND_MAP, layout, |e, out| {
+            e.key().encode(out);
+            e.value().encode(out);
+        })
+    }
+}
+
+impl<K, V, H, P> IntoIterator for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = (K, V);
+    type IntoIter = MapIntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        MapIntoIter {
+            _inner_iter: self._hash_map_internal.into_iter_head(),
+        }
+    }
+}
+
+impl<'a, K, V, H, P> IntoIterator for &'a DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = MapIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_head()
+    }
+}
+
+impl<'a, K, V, H, P> IntoIterator for &'a mut DynFixedHashMap<K, V, H, P>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
+    P: Probe,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = MapIterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, Q, V, H, P> Index<&Q> for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + Borrow<Q>,
+    Q: Hash + std::cmp::Eq + ?Sized,
+    H: Default + Hasher,
+    P: Probe,
 {
     type Output = V;
-    fn index(&self, key: &K) -> &Self::Output {
+    fn index(&self, key: &Q) -> &Self::Output {
         self.get(key).expect("Panic! not in map")
     }
 }
 
-impl<K, V, const C: usize, H> IndexMut<&K> for FixedSizeHashMap<K, V, C, H>
+impl<K, Q, V, H, P> IndexMut<&Q> for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + Borrow<Q>,
+    Q: Hash + std::cmp::Eq + ?Sized,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn index_mut(&mut self, key: &Q) -> &mut Self::Output {
+        self.get_mut(key).expect("Panic! not in map")
+    }
+}
+
+impl<K, V, H, P> Clone for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + Clone,
+    V: Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _hash_map_internal: self._hash_map_internal.clone(),
+        }
+    }
+}
+
+impl<K, V, H, P> fmt::Debug for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + fmt::Debug,
+    V: fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter_head()).finish()
+    }
+}
+
+// Maps are equal when they hold the same entries, whatever their order or capacity.
+impl<K, V, H, P> PartialEq for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    V: PartialEq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.iter_head().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K, V, H, P> Eq for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    V: Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+// Panics once the map is full, use try_extend to get the rejected entry back instead.
+impl<K, V, H, P> Extend<(K, V)> for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        if let Err(error) = self.try_extend(iter) {
+            panic!("{}", error);
+        }
+    }
+}
+
+// Compile time checked capacity. All map operations are provided by the DynFixedHashMap it
+// dereferences to.
+#[cfg(feature = "nightly")]
+pub struct FixedSizeHashMap<K, V, const C: usize, H = DefaultHasher, P = LinearProbe>
 where
     Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
+    P: Probe,
 {
-    fn index_mut(&mut self, key: &K) -> &mut Self::Output {
-        self.get_mut(key).expect("Panic! not in map")
+    _hash_map: DynFixedHashMap<K, V, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Default for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-pub struct MapIter<'a, K: 'a, V: 'a, const C: usize> {
-    _inner_iter: MapIteratorImpl<'a, K, V, MapEntry<K, V, C>, C>,
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    const CAPACITY: usize = C;
+    type _Hash = H;
+
+    pub fn placeholder() -> FixedSizeHashMap<K, V, C, H, P> {
+        FixedSizeHashMap::<K, V, C, H, P> {
+            _hash_map: DynFixedHashMap::placeholder(C),
+        }
+    }
+
+    pub fn new() -> FixedSizeHashMap<K, V, C, H, P> {
+        FixedSizeHashMap::<K, V, C, H, P> {
+            _hash_map: DynFixedHashMap::new(C),
+        }
+    }
+
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, InsertError<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        Ok(Self {
+            _hash_map: DynFixedHashMap::try_from_iter(C, iter)?,
+        })
+    }
+
+    // Fails with CapacityMismatch unless the snapshot was written by a map of capacity C.
+    pub fn from_snapshot(bytes: &[u8]) -> Result<Self, SnapshotError>
+    where
+        K: SnapshotCodec,
+        V: SnapshotCodec,
+    {
+        Ok(Self {
+            _hash_map: DynFixedHashMap::_from_snapshot(bytes, Some(C))?,
+        })
+    }
+
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    pub fn grow_into<const C2: usize>(self) -> FixedSizeHashMap<K, V, C2, H, P>
+    where
+        Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+        Check<{ C2 >= C }>: IsTrue,
+    {
+        let mut hash_map = self._hash_map;
+        hash_map.grow(C2);
+        FixedSizeHashMap {
+            _hash_map: hash_map,
+        }
+    }
 }
 
-impl<'a, K: 'a, V: 'a, const C: usize> Iterator for MapIter<'a, K, V, C> {
-    type Item = (&'a K, &'a V);
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Deref for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynFixedHashMap<K, V, H, P>;
+    fn deref(&self) -> &Self::Target {
+        &self._hash_map
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self._inner_iter.next().map(|e| (e.key(), e.value()))
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> DerefMut for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._hash_map
     }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        self._inner_iter.size_hint()
+#[cfg(feature = "nightly")]
+impl<K, Q, V, const C: usize, H, P> Index<&Q> for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Borrow<Q>,
+    Q: Hash + std::cmp::Eq + ?Sized,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Output = V;
+    fn index(&self, key: &Q) -> &Self::Output {
+        &self._hash_map[key]
     }
+}
 
-    fn count(self) -> usize {
-        self._inner_iter.count()
+#[cfg(feature = "nightly")]
+impl<K, Q, V, const C: usize, H, P> IndexMut<&Q> for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Borrow<Q>,
+    Q: Hash + std::cmp::Eq + ?Sized,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn index_mut(&mut self, key: &Q) -> &mut Self::Output {
+        &mut self._hash_map[key]
     }
 }
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> IntoIterator for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = (K, V);
+    type IntoIter = MapIntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self._hash_map.into_iter()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, K, V, const C: usize, H, P> IntoIterator for &'a FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = (&'a K, &'a V);
+    type IntoIter = MapIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self._hash_map.iter_head()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, K, V, const C: usize, H, P> IntoIterator for &'a mut FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = MapIterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self._hash_map.iter_mut()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Clone for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Clone,
+    V: Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _hash_map: self._hash_map.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> fmt::Debug for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + fmt::Debug,
+    V: fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self._hash_map.fmt(f)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> PartialEq for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    V: PartialEq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self._hash_map == other._hash_map
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Eq for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    V: Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Extend<(K, V)> for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self._hash_map.extend(iter);
+    }
+}
+
+pub enum Entry<'a, K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    Occupied(OccupiedEntry<'a, K, V, H, P>),
+    Vacant(VacantEntry<'a, K, V, H, P>),
+}
+
+impl<'a, K, V, H, P> Entry<'a, K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub(crate) fn from_map(hash_map_internal: &'a mut MapImpl<K, V, H, P>, key: K) -> Self {
+        let hash = hash_map_internal.hash_of(&key);
+        match hash_map_internal.find_slot(hash, &key) {
+            SlotLookup::Occupied(index) => Entry::Occupied(OccupiedEntry {
+                _hash_map_internal: hash_map_internal,
+                _index: index,
+            }),
+            SlotLookup::Vacant(index) => Entry::Vacant(VacantEntry {
+                _hash_map_internal: hash_map_internal,
+                _key: key,
+                _hash: hash,
+                _index: index,
+            }),
+        }
+    }
+
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> Result<&'a mut V, InsertError<K, V>> {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> Result<&'a mut V, InsertError<K, V>> {
+        match self {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> Result<&'a mut V, InsertError<K, V>>
+    where
+        V: Default,
+    {
+        self.or_insert_with(Default::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(self, op: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                op(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+pub struct OccupiedEntry<'a, K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _hash_map_internal: &'a mut MapImpl<K, V, H, P>,
+    _index: usize,
+}
+
+impl<'a, K, V, H, P> OccupiedEntry<'a, K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn _entry(&self) -> &MapEntry<K, V> {
+        self._hash_map_internal
+            .get_entry_at(self._index)
+            .expect("occupied entry must point at an occupied slot")
+    }
+
+    fn _mut_entry(&mut self) -> &mut MapEntry<K, V> {
+        self._hash_map_internal
+            .get_mut_entry_at(self._index)
+            .expect("occupied entry must point at an occupied slot")
+    }
+
+    pub fn key(&self) -> &K {
+        self._entry().key()
+    }
+
+    pub fn get(&self) -> &V {
+        self._entry().value()
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        self._mut_entry().mut_value()
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        self._hash_map_internal
+            .get_mut_entry_at(self._index)
+            .expect("occupied entry must point at an occupied slot")
+            .mut_value()
+    }
+
+    // Replaces the value in place, the entry keeps its position in the list.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        self._hash_map_internal
+            .remove_at(self._index)
+            .expect("occupied entry must point at an occupied slot")
+            .into_key_value()
+    }
+}
+
+pub struct VacantEntry<'a, K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _hash_map_internal: &'a mut MapImpl<K, V, H, P>,
+    _key: K,
+    _hash: u64,
+    _index: usize,
+}
+
+impl<'a, K, V, H, P> VacantEntry<'a, K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn key(&self) -> &K {
+        &self._key
+    }
+
+    pub fn into_key(self) -> K {
+        self._key
+    }
+
+    pub fn insert(self, value: V) -> Result<&'a mut V, InsertError<K, V>> {
+        self._hash_map_internal
+            .insert_at(self._index, self._hash, self._key, value)
+            .map(|e| e.mut_value())
+    }
+}
+
+pub struct MapIter<'a, K: 'a, V: 'a> {
+    pub(crate) _inner_iter: MapIteratorImpl<'a, K, V, MapEntry<K, V>>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for MapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| (e.key(), e.value()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+
+    fn count(self) -> usize {
+        self._inner_iter.count()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for MapIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| (e.key(), e.value()))
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for MapIter<'a, K, V> {}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for MapIter<'a, K, V> {}
+
+pub struct MapExtractIf<'a, K: 'a, V: 'a, H, P, F>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    _inner_iter: ExtractIfImpl<'a, K, V, H, MapEntry<K, V>, P>,
+    _pred: F,
+}
+
+impl<'a, K: 'a, V: 'a, H, P, F> Iterator for MapExtractIf<'a, K, V, H, P, F>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pred = &mut self._pred;
+        self._inner_iter
+            .next_matching(|e| {
+                let (key, value) = e.key_and_mut_value();
+                pred(key, value)
+            })
+            .map(|e| e.into_key_value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, H, P, F> FusedIterator for MapExtractIf<'a, K, V, H, P, F>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+    F: FnMut(&K, &mut V) -> bool,
+{
+}
+
+pub struct MapIterMut<'a, K: 'a, V: 'a> {
+    _inner_iter: MapIteratorMutImpl<'a, K, V, MapEntry<K, V>>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for MapIterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.key_and_mut_value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for MapIterMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| e.key_and_mut_value())
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for MapIterMut<'a, K, V> {}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for MapIterMut<'a, K, V> {}
+
+pub struct MapKeys<'a, K: 'a, V: 'a> {
+    _inner_iter: MapIteratorImpl<'a, K, V, MapEntry<K, V>>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for MapKeys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.key())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for MapKeys<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| e.key())
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for MapKeys<'a, K, V> {}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for MapKeys<'a, K, V> {}
+
+pub struct MapValues<'a, K: 'a, V: 'a> {
+    _inner_iter: MapIteratorImpl<'a, K, V, MapEntry<K, V>>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for MapValues<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for MapValues<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| e.value())
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for MapValues<'a, K, V> {}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for MapValues<'a, K, V> {}
+
+pub struct MapValuesMut<'a, K: 'a, V: 'a> {
+    _inner_iter: MapIteratorMutImpl<'a, K, V, MapEntry<K, V>>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for MapValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.mut_value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> DoubleEndedIterator for MapValuesMut<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| e.mut_value())
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for MapValuesMut<'a, K, V> {}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for MapValuesMut<'a, K, V> {}
+
+pub struct MapIntoIter<K, V> {
+    _inner_iter: IntoIteratorImpl<K, V, MapEntry<K, V>>,
+}
+
+impl<K, V> Iterator for MapIntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.into_key_value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for MapIntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| e.into_key_value())
+    }
+}
+
+impl<K, V> ExactSizeIterator for MapIntoIter<K, V> {}
+
+impl<K, V> FusedIterator for MapIntoIter<K, V> {}
+
+pub struct MapDrain<'a, K: 'a, V: 'a> {
+    _inner_iter: DrainImpl<'a, K, V, MapEntry<K, V>>,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for MapDrain<'a, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.into_key_value())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, K: 'a, V: 'a> ExactSizeIterator for MapDrain<'a, K, V> {}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for MapDrain<'a, K, V> {}
@@ -1,32 +1,28 @@
 #![allow(dead_code)]
 
+use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
+use std::iter::FusedIterator;
 use std::marker::PhantomData;
 use std::mem;
 
 use crate::{
-    check::{Check, IsTrue, is_prime_and_within_limit},
-    OutOfCapacityError
+    probe::Probe,
+    errors::InsertError,
 };
 
+#[derive(Default, Clone, Debug)]
 pub enum Slot<T> {
+    #[default]
     Empty,
     WasOccupied,
     IsOccupiedBy(T),
 }
 
-impl<T> Default for Slot<T> {
-    fn default() -> Self {
-        Self::Empty
-    }
-}
-
 impl<T> Slot<T> {
     fn is_occupied(&self) -> bool {
-        match self {
-            Self::IsOccupiedBy(_) => true,
-            _ => false,
-        }
+        matches!(self, Self::IsOccupiedBy(_))
     }
 
     fn take(&mut self) -> Option<T> {
@@ -40,87 +36,220 @@ impl<T> Slot<T> {
     }
 }
 
-pub(crate) trait Entry<K, V, const C: usize> {
+pub(crate) trait Entry<K, V> {
     fn key(&self) -> &K;
     fn value(&self) -> &V;
     fn consume_self(self) -> V;
+    fn into_key_value(self) -> (K, V);
     fn mut_value(&mut self) -> &mut V;
     fn next(&self) -> usize;
     fn mut_next(&mut self) -> &mut usize;
     fn prev(&self) -> usize;
     fn mut_prev(&mut self) -> &mut usize;
-    fn new(key: K, value: V) -> Self;
+    fn hash(&self) -> u64;
+    fn new(key: K, value: V, hash: u64) -> Self;
+    fn remap_indices(&mut self, _new_index_of: &[usize]) {}
 }
 
-pub(crate) struct FixedSizeHashMapImpl<K, V, const C: usize, H, E>
+pub(crate) struct FixedSizeHashMapImpl<K, V, H, E, P>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
-    E: Entry<K, V, C>,
+    E: Entry<K, V>,
+    P: Probe,
 {
     _data: Vec<Slot<E>>,
+    _capacity: usize,
     _size: usize,
+    _tombstones: usize,
     _head: usize,
     _tail: usize,
-    _phantom: PhantomData<(K, V, H)>,
+    _phantom: PhantomData<(K, V, H, P)>,
 }
 
-impl<K, V, const C: usize, H, E> Default for FixedSizeHashMapImpl<K, V, C, H, E>
+impl<K, V, H, E, P> Clone for FixedSizeHashMapImpl<K, V, H, E, P>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
-    E: Entry<K, V, C>,
+    E: Entry<K, V> + Clone,
+    P: Probe,
 {
-    fn default() -> Self {
-        Self::new()
+    // Slots are copied as they are, so list links and any indices held by entries stay valid.
+    fn clone(&self) -> Self {
+        Self {
+            _data: self._data.clone(),
+            _capacity: self._capacity,
+            _size: self._size,
+            _tombstones: self._tombstones,
+            _head: self._head,
+            _tail: self._tail,
+            _phantom: Default::default(),
+        }
     }
 }
 
-#[derive(PartialEq)]
-enum FindIndexPurpose {
-    FindSlotForInsertion,
-    FindIfEntryExists,
+// Result of probing for a key: the slot holding it, or the slot a new entry for it should go
+// into. Vacant(CAPACITY) means there is no room left. With Robin Hood probing a vacant slot may
+// still be occupied by another entry that has to be shifted out first.
+pub(crate) enum SlotLookup {
+    Occupied(usize),
+    Vacant(usize),
 }
 
 // hash_map Internals
-impl<K, V, const C: usize, H, E> FixedSizeHashMapImpl<K, V, C, H, E>
+impl<K, V, H, E, P> FixedSizeHashMapImpl<K, V, H, E, P>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
-    E: Entry<K, V, C>,
+    E: Entry<K, V>,
+    P: Probe,
 {
-    fn _find_index(&self, key: &K, purpose: FindIndexPurpose) -> usize {
-        let mut hash_state: Self::_Hash = Default::default();
-        key.hash(&mut hash_state);
-        let already_visited = (hash_state.finish() % Self::CAPACITY as u64) as usize;
-        let mut index = already_visited;
-        let mut key_not_found = true;
-
-        while match self._data[index] {
-            Slot::IsOccupiedBy(ref entry) => {
-                key_not_found = *entry.key() != *key;
-                key_not_found
+    fn _displacement(hash: u64, i: usize, capacity: usize) -> usize {
+        let home = P::index(hash, 0, capacity);
+        (i + capacity - home) % capacity
+    }
+
+    fn _find_index<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        match self._lookup(self.hash_of(key), key) {
+            SlotLookup::Occupied(index) => index,
+            SlotLookup::Vacant(_) => self._capacity,
+        }
+    }
+
+    fn _lookup<Q>(&self, hash: u64, key: &Q) -> SlotLookup
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let mut first_free_slot = self._capacity;
+
+        for step in 0..self._capacity {
+            let index = P::index(hash, step, self._capacity);
+            match self._data[index] {
+                Slot::IsOccupiedBy(ref entry) => {
+                    if entry.hash() == hash && entry.key().borrow() == key {
+                        return SlotLookup::Occupied(index);
+                    }
+                    if P::ROBIN_HOOD
+                        && Self::_displacement(entry.hash(), index, self._capacity) < step
+                    {
+                        if first_free_slot == self._capacity && self._size < self._capacity {
+                            first_free_slot = index;
+                        }
+                        break;
+                    }
+                }
+                Slot::WasOccupied => {
+                    if first_free_slot == self._capacity {
+                        first_free_slot = index;
+                    }
+                }
+                Slot::Empty => {
+                    if first_free_slot == self._capacity {
+                        first_free_slot = index;
+                    }
+                    break;
+                }
             }
-            Slot::WasOccupied => purpose == FindIndexPurpose::FindIfEntryExists,
-            Slot::Empty => false,
-        } {
-            index = (index + 1) % Self::CAPACITY; // linear probing
-            if index == already_visited {
-                return Self::CAPACITY;
+        }
+
+        SlotLookup::Vacant(first_free_slot)
+    }
+
+    fn _place_at(&mut self, i: usize, mut entry: E) {
+        match self._data[i] {
+            Slot::IsOccupiedBy(_) => self._shift_out(i),
+            Slot::WasOccupied => self._tombstones -= 1,
+            Slot::Empty => {}
+        }
+
+        *entry.mut_next() = self._capacity;
+        *entry.mut_prev() = self._capacity;
+        self._data[i] = Slot::IsOccupiedBy(entry);
+        self._add_to_list(i);
+    }
+
+    // Robin Hood insertion: carries the entry at i, and every entry it has to displace in turn,
+    // further along the (linear) probe sequence until a free slot is found, leaving slot i free.
+    fn _shift_out(&mut self, i: usize) {
+        debug_assert!(P::ROBIN_HOOD && self._size < self._capacity && self._tombstones == 0);
+
+        let Slot::IsOccupiedBy(mut carried) = mem::take(&mut self._data[i]) else {
+            return;
+        };
+        let mut carried_from = i;
+        let mut carried_displacement = Self::_displacement(carried.hash(), i, self._capacity);
+        let mut moves: Vec<(usize, usize)> = Vec::new();
+        let mut index = i;
+
+        loop {
+            index = (index + 1) % self._capacity;
+            carried_displacement += 1;
+            match self._data[index] {
+                Slot::IsOccupiedBy(ref mut entry) => {
+                    let displacement = Self::_displacement(entry.hash(), index, self._capacity);
+                    if displacement < carried_displacement {
+                        mem::swap(entry, &mut carried);
+                        moves.push((carried_from, index));
+                        carried_from = index;
+                        carried_displacement = displacement;
+                    }
+                }
+                Slot::WasOccupied | Slot::Empty => {
+                    self._data[index] = Slot::IsOccupiedBy(carried);
+                    moves.push((carried_from, index));
+                    break;
+                }
             }
         }
-        if FindIndexPurpose::FindIfEntryExists == purpose && key_not_found {
-            index = Self::CAPACITY;
+
+        self._relink_moved(&moves);
+    }
+
+    // Fixes up the list after entries were moved between slots. Each entry still holds the old
+    // slot indices of its neighbours, some of which may have moved as well.
+    fn _relink_moved(&mut self, moves: &[(usize, usize)]) {
+        let new_index_of = |i: usize| {
+            moves
+                .iter()
+                .find(|(from, _)| *from == i)
+                .map_or(i, |(_, to)| *to)
+        };
+
+        for &(_, to) in moves {
+            if let Slot::IsOccupiedBy(ref mut entry) = self._data[to] {
+                *entry.mut_prev() = new_index_of(entry.prev());
+                *entry.mut_next() = new_index_of(entry.next());
+            }
+        }
+
+        for &(_, to) in moves {
+            let (prev, next) = match self._data[to] {
+                Slot::IsOccupiedBy(ref entry) => (entry.prev(), entry.next()),
+                _ => continue,
+            };
+
+            if prev == self._capacity {
+                self._head = to;
+            } else if let Slot::IsOccupiedBy(ref mut prev_entry) = self._data[prev] {
+                *prev_entry.mut_next() = to;
+            }
+
+            if next == self._capacity {
+                self._tail = to;
+            } else if let Slot::IsOccupiedBy(ref mut next_entry) = self._data[next] {
+                *next_entry.mut_prev() = to;
+            }
         }
-        index
     }
 
     fn _add_to_list(&mut self, i: usize) {
         if self._size == 0 {
-            debug_assert!(self._head == Self::CAPACITY && self._tail == Self::CAPACITY);
+            debug_assert!(self._head == self._capacity && self._tail == self._capacity);
             self._head = i;
             self._tail = i;
         } else {
@@ -130,7 +259,7 @@ where
 
             if let Slot::IsOccupiedBy(ref mut entry) = self._data[i] {
                 *entry.mut_prev() = self._tail;
-                *entry.mut_next() = Self::CAPACITY;
+                *entry.mut_next() = self._capacity;
             }
 
             self._tail = i;
@@ -138,38 +267,168 @@ where
         self._size += 1;
     }
 
+    fn _link_after(&mut self, i: usize, j: usize) {
+        let next = match self.get_entry_at(j) {
+            Some(entry) => entry.next(),
+            None => self._head,
+        };
+
+        if let Slot::IsOccupiedBy(ref mut entry) = self._data[i] {
+            *entry.mut_prev() = j;
+            *entry.mut_next() = next;
+        }
+
+        if j == self._capacity {
+            self._head = i;
+        } else if let Slot::IsOccupiedBy(ref mut prev_entry) = self._data[j] {
+            *prev_entry.mut_next() = i;
+        }
+
+        if next == self._capacity {
+            self._tail = i;
+        } else if let Slot::IsOccupiedBy(ref mut next_entry) = self._data[next] {
+            *next_entry.mut_prev() = i;
+        }
+
+        self._size += 1;
+    }
+
     fn _move_to_back_of_list(&mut self, i: usize) {
         debug_assert!(self._size != 0);
 
-        if self._size > 1 {
-            if let Slot::IsOccupiedBy(ref mut tail_entry) = self._data[self._tail] {
-                *tail_entry.mut_next() = i;
-            }
+        if i != self._tail {
+            self._remove_from_list(i);
+            self._add_to_list(i);
+        }
+    }
 
+    // Links the entries in the given order. Every occupied slot must appear exactly once.
+    fn _relink(&mut self, order: &[usize]) {
+        let mut prev = self._capacity;
+        for (n, &i) in order.iter().enumerate() {
+            let next = order.get(n + 1).copied().unwrap_or(self._capacity);
             if let Slot::IsOccupiedBy(ref mut entry) = self._data[i] {
-                *entry.mut_prev() = self._tail;
-                *entry.mut_next() = Self::CAPACITY;
+                *entry.mut_prev() = prev;
+                *entry.mut_next() = next;
             }
+            prev = i;
+        }
+        self._head = order.first().copied().unwrap_or(self._capacity);
+        self._tail = prev;
+    }
 
-            self._tail = i;
+    fn _take_at(&mut self, i: usize) -> Option<E> {
+        if i == self._capacity || !self._data[i].is_occupied() {
+            return None;
+        }
+
+        self._remove_from_list(i);
+
+        if P::ROBIN_HOOD {
+            let old_entry = mem::take(&mut self._data[i]);
+            self._shift_back(i);
+            return match old_entry {
+                Slot::IsOccupiedBy(entry) => Some(entry),
+                _ => None,
+            };
         }
+
+        self._tombstones += 1;
+        self._data[i].take()
+    }
+
+    // Robin Hood deletion: instead of leaving a tombstone, which would break the displacement
+    // ordering lookups rely on, every following displaced entry moves one slot back.
+    fn _shift_back(&mut self, i: usize) {
+        let mut moves: Vec<(usize, usize)> = Vec::new();
+        let mut free = i;
+
+        for _ in 1..self._capacity {
+            let index = (free + 1) % self._capacity;
+            let displaced = match self._data[index] {
+                Slot::IsOccupiedBy(ref entry) => {
+                    Self::_displacement(entry.hash(), index, self._capacity) > 0
+                }
+                _ => false,
+            };
+            if !displaced {
+                break;
+            }
+            self._data[free] = mem::take(&mut self._data[index]);
+            moves.push((index, free));
+            free = index;
+        }
+
+        self._relink_moved(&moves);
+    }
+
+    fn _rebuild(&mut self, capacity: usize) {
+        let mut old_indices: Vec<usize> = Vec::with_capacity(self._size);
+        let mut entries: Vec<E> = Vec::with_capacity(self._size);
+        let mut i = self._head;
+        while i != self._capacity {
+            let Slot::IsOccupiedBy(entry) = mem::take(&mut self._data[i]) else {
+                unreachable!("list must only link occupied slots");
+            };
+            old_indices.push(i);
+            i = entry.next();
+            entries.push(entry);
+        }
+
+        let old_capacity = self._capacity;
+        self._data.clear();
+        self._data.resize_with(capacity, Default::default);
+        self._capacity = capacity;
+        self._size = 0;
+        self._tombstones = 0;
+        self._head = self._capacity;
+        self._tail = self._capacity;
+
+        for entry in entries {
+            if let SlotLookup::Vacant(new_index) = self._lookup(entry.hash(), entry.key()) {
+                self._place_at(new_index, entry);
+            }
+        }
+
+        // Robin Hood placement may move entries again, so read the final slots off the list.
+        let mut new_index_of = vec![self._capacity; old_capacity];
+        let mut new_index = self._head;
+        for old_index in old_indices {
+            new_index_of[old_index] = new_index;
+            if let Slot::IsOccupiedBy(ref entry) = self._data[new_index] {
+                new_index = entry.next();
+            }
+        }
+
+        for slot in self._data.iter_mut() {
+            if let Slot::IsOccupiedBy(ref mut entry) = *slot {
+                entry.remap_indices(&new_index_of);
+            }
+        }
+    }
+
+    // Compacts once a quarter of the slots are tombstones. A full table needs no special case:
+    // lookups are bounded by the capacity and inserts reuse tombstones. Robin Hood tables only
+    // get tombstones from take_at and always need compacting.
+    fn _needs_compaction(&self) -> bool {
+        self._tombstones > 0 && (P::ROBIN_HOOD || self._tombstones * 4 > self._capacity)
     }
 
     fn _remove_from_list(&mut self, i: usize) {
         debug_assert!(self._size != 0);
 
-        let mut entry_next = Self::CAPACITY;
-        let mut entry_prev = Self::CAPACITY;
+        let mut entry_next = self._capacity;
+        let mut entry_prev = self._capacity;
         if let Slot::IsOccupiedBy(ref mut entry) = self._data[i] {
-            entry_next = mem::replace(entry.mut_next(), Self::CAPACITY);
-            entry_prev = mem::replace(entry.mut_prev(), Self::CAPACITY);
+            entry_next = mem::replace(entry.mut_next(), self._capacity);
+            entry_prev = mem::replace(entry.mut_prev(), self._capacity);
         }
 
         if self._size == 1 {
-            self._head = Self::CAPACITY;
-            self._tail = Self::CAPACITY;
+            self._head = self._capacity;
+            self._tail = self._capacity;
         } else {
-            if entry_prev != Self::CAPACITY {
+            if entry_prev != self._capacity {
                 if let Slot::IsOccupiedBy(ref mut prev_entry) = self._data[entry_prev] {
                     *prev_entry.mut_next() = entry_next;
                 }
@@ -177,7 +436,7 @@ where
                 self._head = entry_next;
             }
 
-            if entry_next != Self::CAPACITY {
+            if entry_next != self._capacity {
                 if let Slot::IsOccupiedBy(ref mut next_entry) = self._data[entry_next] {
                     *next_entry.mut_prev() = entry_prev;
                 }
@@ -189,50 +448,99 @@ where
     }
 }
 
-impl<K, V, const C: usize, H, E> FixedSizeHashMapImpl<K, V, C, H, E>
+impl<K, V, H, E, P> FixedSizeHashMapImpl<K, V, H, E, P>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     K: Hash + std::cmp::Eq,
     H: Default + Hasher,
-    E: Entry<K, V, C>,
+    E: Entry<K, V>,
+    P: Probe,
 {
-    pub const CAPACITY: usize = C;
-    type _Hash = H;
-
-    pub fn placeholder() -> FixedSizeHashMapImpl<K, V, C, H, E> {
-        FixedSizeHashMapImpl::<K, V, C, H, E> {
+    pub fn placeholder(capacity: usize) -> FixedSizeHashMapImpl<K, V, H, E, P> {
+        FixedSizeHashMapImpl::<K, V, H, E, P> {
             _data: Vec::new(),
+            _capacity: capacity,
             _size: 0,
-            _head: Self::CAPACITY,
-            _tail: Self::CAPACITY,
-            _phantom: PhantomData::<(K, V, H)>{},
+            _tombstones: 0,
+            _head: capacity,
+            _tail: capacity,
+            _phantom: PhantomData::<(K, V, H, P)>{},
         }
     }
 
-    pub fn new() -> FixedSizeHashMapImpl<K, V, C, H, E> {
-        let mut map = FixedSizeHashMapImpl::<K, V, C, H, E> {
+    pub fn new(capacity: usize) -> FixedSizeHashMapImpl<K, V, H, E, P> {
+        let mut map = FixedSizeHashMapImpl::<K, V, H, E, P> {
             _data: Vec::new(),
+            _capacity: capacity,
             _size: 0,
-            _head: Self::CAPACITY,
-            _tail: Self::CAPACITY,
+            _tombstones: 0,
+            _head: capacity,
+            _tail: capacity,
             _phantom: Default::default(),
         };
-        map._data.resize_with(Self::CAPACITY, Default::default);
+        map._data.resize_with(capacity, Default::default);
         map
     }
 
-    pub fn get_index_of(&self, key: &K) -> Option<usize> {
-        let i = self._find_index(key, FindIndexPurpose::FindIfEntryExists);
-        if i != Self::CAPACITY && self._data[i].is_occupied() {
+    // Reassembles a table from a slot array written by an earlier table with the same capacity,
+    // hasher and probe. None unless the list links every occupied slot exactly once.
+    pub fn from_slots(data: Vec<Slot<E>>, head: usize, tail: usize) -> Option<Self> {
+        let capacity = data.len();
+        let size = data.iter().filter(|slot| slot.is_occupied()).count();
+        let tombstones = data.iter().filter(|slot| matches!(slot, Slot::WasOccupied)).count();
+
+        let (mut i, mut prev, mut visited) = (head, capacity, 0);
+        while i != capacity {
+            let Some(Slot::IsOccupiedBy(entry)) = data.get(i) else {
+                return None;
+            };
+            if entry.prev() != prev || visited == size {
+                return None;
+            }
+            (prev, i, visited) = (i, entry.next(), visited + 1);
+        }
+        if visited != size || prev != tail {
+            return None;
+        }
+
+        Some(Self {
+            _data: data,
+            _capacity: capacity,
+            _size: size,
+            _tombstones: tombstones,
+            _head: head,
+            _tail: tail,
+            _phantom: Default::default(),
+        })
+    }
+
+    pub fn slots(&self) -> &[Slot<E>] {
+        &self._data
+    }
+
+    pub fn tail_index(&self) -> usize {
+        self._tail
+    }
+
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let i = self._find_index(key);
+        if i != self._capacity && self._data[i].is_occupied() {
             Some(i)
         } else {
             None
         }
     }
 
-    pub fn get_mut_entry_and_index_of(&mut self, key: &K) -> Option<(&mut E, usize)> {
-        let i = self._find_index(key, FindIndexPurpose::FindIfEntryExists);
-        if i != Self::CAPACITY
+    pub fn get_mut_entry_and_index_of<Q>(&mut self, key: &Q) -> Option<(&mut E, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let i = self._find_index(key);
+        if i != self._capacity
             && let Slot::IsOccupiedBy(ref mut entry) = self._data[i]
         {
             Some((entry, i))
@@ -241,9 +549,13 @@ where
         }
     }
 
-    pub fn get_entry_and_index_of(&self, key: &K) -> Option<(&E, usize)> {
-        let i = self._find_index(key, FindIndexPurpose::FindIfEntryExists);
-        if i != Self::CAPACITY
+    pub fn get_entry_and_index_of<Q>(&self, key: &Q) -> Option<(&E, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let i = self._find_index(key);
+        if i != self._capacity
             && let Slot::IsOccupiedBy(ref entry) = self._data[i]
         {
             Some((entry, i))
@@ -253,7 +565,7 @@ where
     }
 
     pub fn get_mut_entry_at(&mut self, i: usize) -> Option<&mut E> {
-        if i != Self::CAPACITY
+        if i != self._capacity
             && let Slot::IsOccupiedBy(ref mut entry) = self._data[i]
         {
             Some(entry)
@@ -263,7 +575,7 @@ where
     }
 
     pub fn get_entry_at(&self, i: usize) -> Option<&E> {
-        if i != Self::CAPACITY
+        if i != self._capacity
             && let Slot::IsOccupiedBy(ref entry) = self._data[i]
         {
             Some(entry)
@@ -272,63 +584,285 @@ where
         }
     }
 
-    pub fn insert_get_index(
+    pub fn hash_of<Q>(&self, key: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        let mut hash_state = H::default();
+        key.hash(&mut hash_state);
+        hash_state.finish()
+    }
+
+    // Borrowed keys hash like the owned key, so a Vacant slot found for one can take the other.
+    pub fn find_slot<Q>(&self, hash: u64, key: &Q) -> SlotLookup
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._lookup(hash, key)
+    }
+
+    pub fn insert_at(
         &mut self,
+        i: usize,
+        hash: u64,
         key: K,
         value: V,
-    ) -> Result<(usize, Option<V>), OutOfCapacityError> {
-        let i = self._find_index(&key, FindIndexPurpose::FindSlotForInsertion);
-        if i == Self::CAPACITY {
-            return Result::Err(OutOfCapacityError {
-                capacity: Self::CAPACITY,
+    ) -> Result<&mut E, InsertError<K, V>> {
+        if i == self._capacity {
+            return Result::Err(InsertError {
+                capacity: self._capacity,
+                key,
+                value,
             });
         }
 
-        let mut old_val: Option<V> = None;
+        debug_assert!(P::ROBIN_HOOD || !self._data[i].is_occupied());
+
+        self._place_at(i, E::new(key, value, hash));
+
         match self._data[i] {
-            Slot::IsOccupiedBy(ref mut entry) => {
-                old_val = Some(mem::replace(entry.mut_value(), value));
-                self._move_to_back_of_list(i);
-            }
-            Slot::Empty | Slot::WasOccupied => {
-                self._data[i] = Slot::IsOccupiedBy({
-                    let mut e = E::new(key, value);
-                    *e.mut_next() = Self::CAPACITY;
-                    *e.mut_next() = Self::CAPACITY;
-                    e
-                });
-                self._add_to_list(i);
-            }
+            Slot::IsOccupiedBy(ref mut entry) => Ok(entry),
+            _ => unreachable!(),
         }
+    }
 
-        Result::Ok((i, old_val))
+    pub fn remove_at(&mut self, i: usize) -> Option<E> {
+        let old_entry = self._take_at(i);
+        if self._needs_compaction() {
+            self.compact();
+        }
+        old_entry
     }
 
-    pub fn exists(&self, key: &K) -> bool {
-        self._find_index(key, FindIndexPurpose::FindIfEntryExists) != Self::CAPACITY
+    pub fn pop_head(&mut self) -> Option<E> {
+        self.remove_at(self._head)
     }
 
-    pub fn remove(&mut self, key: &K) -> Option<E> {
-        let i = self._find_index(key, FindIndexPurpose::FindIfEntryExists);
-        if i == Self::CAPACITY {
+    pub fn pop_tail(&mut self) -> Option<E> {
+        self.remove_at(self._tail)
+    }
+
+    // Slot of the entry at position n of the list, walking in from the nearer end.
+    pub fn index_at_rank(&self, n: usize) -> Option<usize> {
+        if n >= self._size {
             return None;
         }
+        let (mut i, steps, backwards) = if n < self._size / 2 {
+            (self._head, n, false)
+        } else {
+            (self._tail, self._size - 1 - n, true)
+        };
+        for _ in 0..steps {
+            let entry = self.get_entry_at(i)?;
+            i = if backwards { entry.prev() } else { entry.next() };
+        }
+        Some(i)
+    }
 
-        debug_assert!(self._data[i].is_occupied());
+    // Position of the entry at slot i in the list.
+    pub fn rank_of(&self, i: usize) -> Option<usize> {
+        self.get_entry_at(i)?;
+        let mut j = self._head;
+        let mut rank = 0;
+        while j != i {
+            j = self.get_entry_at(j)?.next();
+            rank += 1;
+        }
+        Some(rank)
+    }
 
+    // Removes entry i and puts the tail entry in its place in the list, like indexmap's
+    // swap_remove.
+    pub fn swap_remove_at(&mut self, i: usize) -> Option<E> {
+        let prev = self.get_entry_at(i)?.prev();
+        if i != self._tail {
+            self.move_after(self._tail, prev);
+        }
+        self.remove_at(i)
+    }
+
+    pub fn move_to_back(&mut self, i: usize) {
+        if self.get_entry_at(i).is_some() {
+            self._move_to_back_of_list(i);
+        }
+    }
+
+    // Relinks entry i right after entry j, or at the front of the list when j is the capacity.
+    pub fn move_after(&mut self, i: usize, j: usize) {
+        if i == j
+            || self.get_entry_at(i).is_none()
+            || (j != self._capacity && self.get_entry_at(j).is_none())
+        {
+            return;
+        }
         self._remove_from_list(i);
+        self._link_after(i, j);
+    }
+
+    pub fn move_to_front(&mut self, i: usize) {
+        self.move_after(i, self._capacity);
+    }
+
+    pub fn move_before(&mut self, i: usize, j: usize) {
+        if let Some(entry) = self.get_entry_at(j) {
+            self.move_after(i, entry.prev());
+        }
+    }
+
+    // Stable sort of the list. Only the links change, every entry stays in its slot.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&E, &E) -> Ordering,
+    {
+        let mut order = Vec::with_capacity(self._size);
+        let mut i = self._head;
+        while let Some(entry) = self.get_entry_at(i) {
+            order.push(i);
+            i = entry.next();
+        }
+
+        order.sort_by(|&a, &b| match (&self._data[a], &self._data[b]) {
+            (Slot::IsOccupiedBy(a), Slot::IsOccupiedBy(b)) => compare(a, b),
+            _ => unreachable!(),
+        });
+        self._relink(&order);
+    }
+
+    // Like remove_at, but always leaves a tombstone and never compacts, so no other entry changes
+    // slot while the caller walks the list. Finish with compact() once needs_compaction() says
+    // so; Robin Hood tables must not be searched before that.
+    pub fn take_at(&mut self, i: usize) -> Option<E> {
+        if i == self._capacity || !self._data[i].is_occupied() {
+            return None;
+        }
 
+        self._remove_from_list(i);
+        self._tombstones += 1;
         self._data[i].take()
     }
 
-    pub const fn capacity(&self) -> usize {
-        Self::CAPACITY
+    pub fn needs_compaction(&self) -> bool {
+        self._needs_compaction()
+    }
+
+    // Removes every entry keep rejects in one walk of the list, compacting once at the end.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&mut E) -> bool,
+    {
+        let mut i = self._head;
+        while i != self._capacity {
+            let Slot::IsOccupiedBy(ref mut entry) = self._data[i] else {
+                unreachable!("list must only link occupied slots");
+            };
+            let next = entry.next();
+            if !keep(entry) {
+                self.take_at(i);
+            }
+            i = next;
+        }
+        if self._needs_compaction() {
+            self.compact();
+        }
+    }
+
+    pub fn extract_if(&mut self) -> ExtractIfImpl<'_, K, V, H, E, P> {
+        ExtractIfImpl {
+            _next: self._head,
+            _map: self,
+        }
+    }
+
+    // Empties every slot, tombstones included.
+    pub fn clear(&mut self) {
+        self._data.iter_mut().for_each(|slot| *slot = Slot::Empty);
+        self._size = 0;
+        self._tombstones = 0;
+        self._head = self._capacity;
+        self._tail = self._capacity;
+    }
+
+    // Rehashes every entry in list order into a freshly emptied slot array, dropping all
+    // tombstones. Entries that refer to other slots are handed the old-to-new index mapping.
+    pub fn compact(&mut self) {
+        if self._tombstones == 0 {
+            return;
+        }
+        self._rebuild(self._capacity);
+    }
+
+    // Moves every entry into a slot array of the given (prime) capacity, keeping the list order.
+    pub fn grow(&mut self, capacity: usize) {
+        debug_assert!(capacity >= self._capacity);
+        if capacity != self._capacity {
+            self._rebuild(capacity);
+        }
+    }
+
+    pub fn tombstone_count(&self) -> usize {
+        self._tombstones
+    }
+
+    pub fn insert_get_index(
+        &mut self,
+        key: K,
+        value: V,
+    ) -> Result<(usize, Option<V>), InsertError<K, V>> {
+        self.upsert_get_index(key, value, true)
+    }
+
+    // Overwriting an existing key moves it to the back of the list only when move_to_back is set.
+    pub fn upsert_get_index(
+        &mut self,
+        key: K,
+        value: V,
+        move_to_back: bool,
+    ) -> Result<(usize, Option<V>), InsertError<K, V>> {
+        let hash = self.hash_of(&key);
+        match self.find_slot(hash, &key) {
+            SlotLookup::Occupied(i) => {
+                let old_val = self
+                    .get_mut_entry_at(i)
+                    .map(|entry| mem::replace(entry.mut_value(), value));
+                if move_to_back {
+                    self._move_to_back_of_list(i);
+                }
+                Result::Ok((i, old_val))
+            }
+            SlotLookup::Vacant(i) => self.insert_at(i, hash, key, value).map(|_| (i, None)),
+        }
+    }
+
+    pub fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._find_index(key) != self._capacity
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<E>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let i = self._find_index(key);
+        self.remove_at(i)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._capacity
     }
 
     pub fn size(&self) -> usize {
         self._size
     }
 
+    pub fn head_index(&self) -> usize {
+        self._head
+    }
+
     pub fn head(&self) -> Option<&E> {
         self.get_entry_at(self._head)
     }
@@ -345,54 +879,100 @@ where
         self.get_mut_entry_at(self._tail)
     }
 
-    pub fn iter_head(&self) -> MapIteratorImpl<'_, K, V, E, C> {
+    pub fn iter_head(&self) -> MapIteratorImpl<'_, K, V, E> {
         MapIteratorImpl {
             _remaining: self._size,
-            _current: self._head,
+            _front: self._head,
+            _back: self._tail,
             _data: &self._data,
             _fn_next: |entry| entry.next(),
+            _fn_prev: |entry| entry.prev(),
             _phantom: Default::default(),
         }
     }
 
-    pub fn iter_tail(&self) -> MapIteratorImpl<'_, K, V, E, C> {
+    pub fn iter_tail(&self) -> MapIteratorImpl<'_, K, V, E> {
         MapIteratorImpl {
             _remaining: self._size,
-            _current: self._tail,
+            _front: self._tail,
+            _back: self._head,
             _data: &self._data,
             _fn_next: |entry| entry.prev(),
+            _fn_prev: |entry| entry.next(),
             _phantom: Default::default(),
         }
     }
+
+    pub fn iter_head_mut(&mut self) -> MapIteratorMutImpl<'_, K, V, E> {
+        MapIteratorMutImpl {
+            _remaining: self._size,
+            _front: self._head,
+            _back: self._tail,
+            _data: self._data.as_mut_ptr(),
+            _phantom: Default::default(),
+        }
+    }
+
+    pub fn into_iter_head(self) -> IntoIteratorImpl<K, V, E> {
+        IntoIteratorImpl {
+            _remaining: self._size,
+            _front: self._head,
+            _back: self._tail,
+            _data: self._data,
+            _phantom: Default::default(),
+        }
+    }
+
+    // The map is already empty once this returns: the drain owns the old slots, so entries the
+    // caller does not consume are dropped with it, and leaking it only leaks those entries.
+    pub fn drain(&mut self) -> DrainImpl<'_, K, V, E> {
+        let mut empty_slots = Vec::new();
+        empty_slots.resize_with(self._capacity, Default::default);
+        let drain = DrainImpl {
+            _remaining: self._size,
+            _front: self._head,
+            _data: mem::replace(&mut self._data, empty_slots),
+            _phantom: Default::default(),
+        };
+        self._size = 0;
+        self._tombstones = 0;
+        self._head = self._capacity;
+        self._tail = self._capacity;
+        drain
+    }
 }
 
-pub(crate) struct MapIteratorImpl<'a, K: 'a, V: 'a, E: 'a, const C: usize>
+// Walks the list from _front towards _back. Both ends can be consumed, _remaining keeps them
+// from crossing.
+pub(crate) struct MapIteratorImpl<'a, K: 'a, V: 'a, E: 'a>
 where
-    E: Entry<K, V, C>,
+    E: Entry<K, V>,
 {
     _remaining: usize,
-    _current: usize,
+    _front: usize,
+    _back: usize,
     _data: &'a Vec<Slot<E>>,
     _fn_next: fn(&E) -> usize,
+    _fn_prev: fn(&E) -> usize,
     _phantom: PhantomData<(K, V)>,
 }
 
-impl<'a, K: 'a, V: 'a, E: 'a, const C: usize> Iterator for MapIteratorImpl<'a, K, V, E, C>
+impl<'a, K: 'a, V: 'a, E: 'a> Iterator for MapIteratorImpl<'a, K, V, E>
 where
-    E: Entry<K, V, C>,
+    E: Entry<K, V>,
 {
     type Item = &'a E;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self._current < C
-            && let Slot::IsOccupiedBy(ref entry) = self._data[self._current]
-        {
-            self._remaining -= 1;
-            self._current = (self._fn_next)(entry);
-            Some(entry)
-        } else {
-            None
+        if self._remaining == 0 {
+            return None;
         }
+        let Slot::IsOccupiedBy(ref entry) = self._data[self._front] else {
+            unreachable!("list must only link occupied slots");
+        };
+        self._remaining -= 1;
+        self._front = (self._fn_next)(entry);
+        Some(entry)
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
@@ -403,3 +983,252 @@ where
         self._remaining
     }
 }
+
+impl<'a, K: 'a, V: 'a, E: 'a> DoubleEndedIterator for MapIteratorImpl<'a, K, V, E>
+where
+    E: Entry<K, V>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let Slot::IsOccupiedBy(ref entry) = self._data[self._back] else {
+            unreachable!("list must only link occupied slots");
+        };
+        self._remaining -= 1;
+        self._back = (self._fn_prev)(entry);
+        Some(entry)
+    }
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> ExactSizeIterator for MapIteratorImpl<'a, K, V, E> where
+    E: Entry<K, V>
+{
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> FusedIterator for MapIteratorImpl<'a, K, V, E> where
+    E: Entry<K, V>
+{
+}
+
+// Same walk as MapIteratorImpl, handing out mutable entries. Holds a raw pointer to the slots
+// because every entry is borrowed mutably for the iterator's whole lifetime.
+pub(crate) struct MapIteratorMutImpl<'a, K: 'a, V: 'a, E: 'a>
+where
+    E: Entry<K, V>,
+{
+    _remaining: usize,
+    _front: usize,
+    _back: usize,
+    _data: *mut Slot<E>,
+    _phantom: PhantomData<(&'a mut Vec<Slot<E>>, K, V)>,
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> MapIteratorMutImpl<'a, K, V, E>
+where
+    E: Entry<K, V>,
+{
+    fn _entry_at(&mut self, i: usize) -> &'a mut E {
+        // SAFETY: i is a slot linked in the list of the map this iterator mutably borrows for
+        // 'a, so it is in bounds and occupied. Every linked slot is visited at most once, from
+        // either end, so no two returned references alias.
+        match unsafe { &mut *self._data.add(i) } {
+            Slot::IsOccupiedBy(entry) => entry,
+            _ => unreachable!("list must only link occupied slots"),
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> Iterator for MapIteratorMutImpl<'a, K, V, E>
+where
+    E: Entry<K, V>,
+{
+    type Item = &'a mut E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let entry = self._entry_at(self._front);
+        self._remaining -= 1;
+        self._front = entry.next();
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self._remaining, Some(self._remaining))
+    }
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> DoubleEndedIterator for MapIteratorMutImpl<'a, K, V, E>
+where
+    E: Entry<K, V>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let entry = self._entry_at(self._back);
+        self._remaining -= 1;
+        self._back = entry.prev();
+        Some(entry)
+    }
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> ExactSizeIterator for MapIteratorMutImpl<'a, K, V, E> where
+    E: Entry<K, V>
+{
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> FusedIterator for MapIteratorMutImpl<'a, K, V, E> where
+    E: Entry<K, V>
+{
+}
+
+pub(crate) struct IntoIteratorImpl<K, V, E>
+where
+    E: Entry<K, V>,
+{
+    _remaining: usize,
+    _front: usize,
+    _back: usize,
+    _data: Vec<Slot<E>>,
+    _phantom: PhantomData<(K, V)>,
+}
+
+impl<K, V, E> Iterator for IntoIteratorImpl<K, V, E>
+where
+    E: Entry<K, V>,
+{
+    type Item = E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let Slot::IsOccupiedBy(entry) = mem::take(&mut self._data[self._front]) else {
+            unreachable!("list must only link occupied slots");
+        };
+        self._remaining -= 1;
+        self._front = entry.next();
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self._remaining, Some(self._remaining))
+    }
+}
+
+impl<K, V, E> DoubleEndedIterator for IntoIteratorImpl<K, V, E>
+where
+    E: Entry<K, V>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let Slot::IsOccupiedBy(entry) = mem::take(&mut self._data[self._back]) else {
+            unreachable!("list must only link occupied slots");
+        };
+        self._remaining -= 1;
+        self._back = entry.prev();
+        Some(entry)
+    }
+}
+
+impl<K, V, E> ExactSizeIterator for IntoIteratorImpl<K, V, E> where E: Entry<K, V> {}
+
+impl<K, V, E> FusedIterator for IntoIteratorImpl<K, V, E> where E: Entry<K, V> {}
+
+pub(crate) struct DrainImpl<'a, K: 'a, V: 'a, E: 'a>
+where
+    E: Entry<K, V>,
+{
+    _remaining: usize,
+    _front: usize,
+    _data: Vec<Slot<E>>,
+    _phantom: PhantomData<(&'a mut E, K, V)>,
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> Iterator for DrainImpl<'a, K, V, E>
+where
+    E: Entry<K, V>,
+{
+    type Item = E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let Slot::IsOccupiedBy(entry) = mem::take(&mut self._data[self._front]) else {
+            unreachable!("list must only link occupied slots");
+        };
+        self._remaining -= 1;
+        self._front = entry.next();
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self._remaining, Some(self._remaining))
+    }
+}
+
+impl<'a, K: 'a, V: 'a, E: 'a> ExactSizeIterator for DrainImpl<'a, K, V, E> where E: Entry<K, V> {}
+
+impl<'a, K: 'a, V: 'a, E: 'a> FusedIterator for DrainImpl<'a, K, V, E> where E: Entry<K, V> {}
+
+// Walks the list once, taking out the entries the caller's predicate picks. Taken slots stay
+// tombstones until the walk is dropped, then the map is compacted if it needs to be.
+pub(crate) struct ExtractIfImpl<'a, K: 'a, V: 'a, H, E: 'a, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    E: Entry<K, V>,
+    P: Probe,
+{
+    _map: &'a mut FixedSizeHashMapImpl<K, V, H, E, P>,
+    _next: usize,
+}
+
+impl<'a, K: 'a, V: 'a, H, E: 'a, P> ExtractIfImpl<'a, K, V, H, E, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    E: Entry<K, V>,
+    P: Probe,
+{
+    pub fn next_matching<F>(&mut self, mut pred: F) -> Option<E>
+    where
+        F: FnMut(&mut E) -> bool,
+    {
+        while self._next != self._map._capacity {
+            let i = self._next;
+            let Slot::IsOccupiedBy(ref mut entry) = self._map._data[i] else {
+                unreachable!("list must only link occupied slots");
+            };
+            self._next = entry.next();
+            if pred(entry) {
+                return self._map.take_at(i);
+            }
+        }
+        None
+    }
+
+    pub fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self._map._size))
+    }
+}
+
+impl<'a, K: 'a, V: 'a, H, E: 'a, P> Drop for ExtractIfImpl<'a, K, V, H, E, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    E: Entry<K, V>,
+    P: Probe,
+{
+    fn drop(&mut self) {
+        if self._map._needs_compaction() {
+            self._map.compact();
+        }
+    }
+}
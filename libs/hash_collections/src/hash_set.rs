@@ -1,81 +1,332 @@
 #![allow(dead_code)]
 
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::iter::FusedIterator;
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
 
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_prime_and_within_limit};
 use crate::{
-    check::{Check, IsTrue, is_prime_and_within_limit},
-    hash_map_internal::{Entry, FixedSizeHashMapImpl, MapIteratorImpl},
-    hash_map::MapEntry,
-    OutOfCapacityError
+    check::prime_at_least,
+    hash_map_internal::{
+        DrainImpl, Entry, ExtractIfImpl, FixedSizeHashMapImpl, IntoIteratorImpl, MapIteratorImpl,
+        SlotLookup,
+    },
+    hash_map::{MapEntry, MapImpl},
+    probe::{LinearProbe, Probe},
+    errors::{InsertError, OutOfCapacityError},
 };
 
-pub struct FixedSizeHashSet<T, const C: usize, H = DefaultHasher>
+// Same table as FixedSizeHashSet, but the capacity is chosen when the set is constructed and is
+// rounded up to the next prime. Needs no nightly features.
+pub struct DynFixedHashSet<T, H = DefaultHasher, P = LinearProbe>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     T: Hash + std::cmp::Eq,
     H: Default + Hasher,
+    P: Probe,
 {
-    _hash_map_internal: FixedSizeHashMapImpl<T, (), C, H, MapEntry<T, (), C>>,
+    _hash_map_internal: MapImpl<T, (), H, P>,
 }
 
-impl<T, const C: usize, H> Default for FixedSizeHashSet<T, C, H>
+impl<T, H, P> DynFixedHashSet<T, H, P>
 where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
     T: Hash + std::cmp::Eq,
     H: Default + Hasher,
+    P: Probe,
 {
-    fn default() -> Self {
-        Self::new()
+    pub fn placeholder(capacity: usize) -> DynFixedHashSet<T, H, P> {
+        DynFixedHashSet::<T, H, P> {
+            _hash_map_internal: FixedSizeHashMapImpl::placeholder(prime_at_least(capacity)),
+        }
     }
-}
 
-impl<T, const C: usize, H> FixedSizeHashSet<T, C, H>
-where
-    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
-    T: Hash + std::cmp::Eq,
-    H: Default + Hasher,
-{
-    const CAPACITY: usize = C;
-    type _Hash = H;
-    
-    pub fn placeholder() -> FixedSizeHashSet<T, C, H> {
-        FixedSizeHashSet::<T, C, H> {
-            _hash_map_internal: FixedSizeHashMapImpl::placeholder(),
+    pub fn new(capacity: usize) -> DynFixedHashSet<T, H, P> {
+        DynFixedHashSet::<T, H, P> {
+            _hash_map_internal: FixedSizeHashMapImpl::new(prime_at_least(capacity)),
         }
     }
 
-    pub fn new() -> FixedSizeHashSet<T, C, H> {
-        FixedSizeHashSet::<T, C, H> {
-            _hash_map_internal: FixedSizeHashMapImpl::new(),
+    // Fallible counterpart of collect(): fails with the first item that does not fit.
+    pub fn try_from_iter<I>(capacity: usize, iter: I) -> Result<Self, InsertError<T, ()>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut set = Self::new(capacity);
+        set.try_extend(iter)?;
+        Ok(set)
+    }
+
+    // Inserts items until one does not fit. Items before it stay inserted, the ones after it
+    // are not consumed.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), InsertError<T, ()>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iter {
+            self.insert(item)?;
         }
+        Ok(())
     }
 
-    pub fn insert(&mut self, item: T) -> Result<bool, OutOfCapacityError> {
-        match self._hash_map_internal.exists(&item) {
-            true => Ok(false),
-            false => {
-                let result = self._hash_map_internal.insert_get_index(item, ());
-                result.map(|_| true)
-            }
+    pub fn insert(&mut self, item: T) -> Result<bool, InsertError<T, ()>> {
+        let hash = self._hash_map_internal.hash_of(&item);
+        match self._hash_map_internal.find_slot(hash, &item) {
+            SlotLookup::Occupied(_) => Ok(false),
+            SlotLookup::Vacant(i) => self
+                ._hash_map_internal
+                .insert_at(i, hash, item, ())
+                .map(|_| true),
         }
     }
 
-    pub fn exists(&self, item: &T) -> bool {
+    pub fn exists<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
         self._hash_map_internal.exists(item)
     }
 
-    pub fn remove(&mut self, item: &T) -> bool {
-        match self._hash_map_internal.exists(item) {
-            false => false,
-            true => {
-                self._hash_map_internal.remove(item);
-                true
+    pub fn remove<Q>(&mut self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal.remove(item).is_some()
+    }
+
+    // The stored item, which may carry more than the parts Eq and Hash look at.
+    pub fn get<Q>(&self, item: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .get_entry_and_index_of(item)
+            .map(|e| e.0.key())
+    }
+
+    // Like remove, but hands back the stored item.
+    pub fn take<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .remove(item)
+            .map(|e| e.into_key_value().0)
+    }
+
+    // Swaps in an equal item at the same position and returns the old one, or inserts it at the
+    // back.
+    pub fn replace(&mut self, item: T) -> Result<Option<T>, InsertError<T, ()>> {
+        let hash = self._hash_map_internal.hash_of(&item);
+        match self._hash_map_internal.find_slot(hash, &item) {
+            SlotLookup::Occupied(i) => Ok(self
+                ._hash_map_internal
+                .get_mut_entry_at(i)
+                .map(|e| e.replace_key(item))),
+            SlotLookup::Vacant(i) => self
+                ._hash_map_internal
+                .insert_at(i, hash, item, ())
+                .map(|_| None),
+        }
+    }
+
+    pub fn get_or_insert(&mut self, item: T) -> Result<&T, InsertError<T, ()>> {
+        let hash = self._hash_map_internal.hash_of(&item);
+        match self._hash_map_internal.find_slot(hash, &item) {
+            SlotLookup::Occupied(i) => Ok(self
+                ._hash_map_internal
+                .get_entry_at(i)
+                .map(|e| e.key())
+                .expect("lookup must point at an occupied slot")),
+            SlotLookup::Vacant(i) => self
+                ._hash_map_internal
+                .insert_at(i, hash, item, ())
+                .map(|e| e.key()),
+        }
+    }
+
+    // Builds the item from the borrowed form only when it is missing.
+    pub fn get_or_insert_with<Q, F>(&mut self, item: &Q, f: F) -> Result<&T, InsertError<T, ()>>
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+        F: FnOnce(&Q) -> T,
+    {
+        let hash = self._hash_map_internal.hash_of(item);
+        match self._hash_map_internal.find_slot(hash, item) {
+            SlotLookup::Occupied(i) => Ok(self
+                ._hash_map_internal
+                .get_entry_at(i)
+                .map(|e| e.key())
+                .expect("lookup must point at an occupied slot")),
+            SlotLookup::Vacant(i) => self
+                ._hash_map_internal
+                .insert_at(i, hash, f(item), ())
+                .map(|e| e.key()),
+        }
+    }
+
+    // Removes the item and moves the last item into its position, like indexmap. O(1).
+    pub fn swap_remove<Q>(&mut self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .get_index_of(item)
+            .and_then(|index| self._hash_map_internal.swap_remove_at(index))
+            .is_some()
+    }
+
+    // Removes the item, keeping the order of the rest. Same as remove.
+    pub fn shift_remove<Q>(&mut self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.remove(item)
+    }
+
+    // Positions count from the head. Reaching position n walks the list from the nearer end.
+    pub fn get_index(&self, n: usize) -> Option<&T> {
+        let index = self._hash_map_internal.index_at_rank(n)?;
+        self._hash_map_internal.get_entry_at(index).map(|e| e.key())
+    }
+
+    pub fn index_of<Q>(&self, item: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(item)?;
+        self._hash_map_internal.rank_of(index)
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.head()
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.tail()
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self._hash_map_internal
+            .pop_head()
+            .map(|e| e.into_key_value().0)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self._hash_map_internal
+            .pop_tail()
+            .map(|e| e.into_key_value().0)
+    }
+
+    // The move_* methods only relink the list and return false when an item is missing.
+    pub fn move_to_front<Q>(&mut self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(index) = self._hash_map_internal.get_index_of(item) else {
+            return false;
+        };
+        self._hash_map_internal.move_to_front(index);
+        true
+    }
+
+    pub fn move_to_back<Q>(&mut self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(index) = self._hash_map_internal.get_index_of(item) else {
+            return false;
+        };
+        self._hash_map_internal.move_to_back(index);
+        true
+    }
+
+    pub fn move_before<Q>(&mut self, item: &Q, anchor: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let (Some(index), Some(anchor_index)) = (
+            self._hash_map_internal.get_index_of(item),
+            self._hash_map_internal.get_index_of(anchor),
+        ) else {
+            return false;
+        };
+        self._hash_map_internal.move_before(index, anchor_index);
+        true
+    }
+
+    // Inserts the item, or moves it if already present, right after anchor. Without the anchor
+    // this is just insert.
+    pub fn insert_after<Q>(&mut self, anchor: &Q, item: T) -> Result<bool, InsertError<T, ()>>
+    where
+        T: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let hash = self._hash_map_internal.hash_of(&item);
+        let (index, inserted) = match self._hash_map_internal.find_slot::<T>(hash, &item) {
+            SlotLookup::Occupied(i) => (i, false),
+            SlotLookup::Vacant(i) => {
+                self._hash_map_internal.insert_at(i, hash, item, ())?;
+                (i, true)
             }
+        };
+        // Looked up after inserting, Robin Hood placement may have moved the anchor.
+        if let Some(anchor_index) = self._hash_map_internal.get_index_of(anchor) {
+            self._hash_map_internal.move_after(index, anchor_index);
         }
+        Ok(inserted)
     }
 
-    pub const fn capacity(&self) -> usize {
-        Self::CAPACITY
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self._hash_map_internal.sort_by(|a, b| compare(a.key(), b.key()));
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut sort_key: F)
+    where
+        K: Ord,
+        F: FnMut(&T) -> K,
+    {
+        self._hash_map_internal
+            .sort_by(|a, b| sort_key(a.key()).cmp(&sort_key(b.key())));
+    }
+
+    // Moves every entry into a table of at least the given capacity, keeping the insertion
+    // order. Never shrinks the set. Only reachable through grow_into for fixed-size sets.
+    pub(crate) fn grow(&mut self, capacity: usize) {
+        let capacity = prime_at_least(capacity);
+        if capacity > self.capacity() {
+            self._hash_map_internal.grow(capacity);
+        }
+    }
+
+    pub fn compact(&mut self) {
+        self._hash_map_internal.compact()
+    }
+
+    pub fn tombstone_count(&self) -> usize {
+        self._hash_map_internal.tombstone_count()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._hash_map_internal.capacity()
     }
 
     pub fn size(&self) -> usize {
@@ -90,24 +341,490 @@ where
         self._hash_map_internal.tail().map(|e| e.key())
     }
 
-    pub fn iter_head(&self) -> SetIter<'_, T, C> {
+    pub fn iter_head(&self) -> SetIter<'_, T> {
         SetIter {
             _inner_iter: self._hash_map_internal.iter_head(),
         }
     }
 
-    pub fn iter_tail(&self) -> SetIter<'_, T, C> {
+    pub fn iter_tail(&self) -> SetIter<'_, T> {
         SetIter {
             _inner_iter: self._hash_map_internal.iter_tail(),
         }
     }
+
+    // Removes every item, yielding them from head to tail. The set is empty even if the drain
+    // is dropped early.
+    pub fn drain(&mut self) -> SetDrain<'_, T> {
+        SetDrain {
+            _inner_iter: self._hash_map_internal.drain(),
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self._hash_map_internal.retain(|e| keep(e.key()));
+    }
+
+    // Lazily removes and yields the items pred picks, in list order. Items not reached before
+    // the iterator is dropped stay in the set.
+    pub fn extract_if<F>(&mut self, pred: F) -> SetExtractIf<'_, T, H, P, F>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        SetExtractIf {
+            _inner_iter: self._hash_map_internal.extract_if(),
+            _pred: pred,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self._hash_map_internal.clear();
+    }
+
+    // The set algebra iterators are lazy and follow this set's insertion order, items only in
+    // other come after in other's order. Collect them with try_from_iter to choose the capacity
+    // of the result.
+    pub fn union<'a>(&'a self, other: &'a DynFixedHashSet<T, H, P>) -> SetUnion<'a, T, H, P> {
+        SetUnion {
+            _left: self.iter_head(),
+            _right_only: other.difference(self),
+        }
+    }
+
+    pub fn intersection<'a>(
+        &'a self,
+        other: &'a DynFixedHashSet<T, H, P>,
+    ) -> SetIntersection<'a, T, H, P> {
+        SetIntersection {
+            _iter: self.iter_head(),
+            _other: other,
+        }
+    }
+
+    pub fn difference<'a>(
+        &'a self,
+        other: &'a DynFixedHashSet<T, H, P>,
+    ) -> SetDifference<'a, T, H, P> {
+        SetDifference {
+            _iter: self.iter_head(),
+            _other: other,
+        }
+    }
+
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a DynFixedHashSet<T, H, P>,
+    ) -> SetSymmetricDifference<'a, T, H, P> {
+        SetSymmetricDifference {
+            _left_only: self.difference(other),
+            _right_only: other.difference(self),
+        }
+    }
+
+    pub fn is_subset(&self, other: &DynFixedHashSet<T, H, P>) -> bool {
+        self.size() <= other.size() && self.iter_head().all(|item| other.exists(item))
+    }
+
+    pub fn is_superset(&self, other: &DynFixedHashSet<T, H, P>) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &DynFixedHashSet<T, H, P>) -> bool {
+        let (smaller, larger) = if self.size() <= other.size() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        !smaller.iter_head().any(|item| larger.exists(item))
+    }
 }
 
-pub struct SetIter<'a, T: 'a, const C: usize> {
-    _inner_iter: MapIteratorImpl<'a, T, (), MapEntry<T, (), C>, C>,
+impl<T, H, P> IntoIterator for DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = T;
+    type IntoIter = SetIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SetIntoIter {
+            _inner_iter: self._hash_map_internal.into_iter_head(),
+        }
+    }
 }
 
-impl<'a, T: 'a, const C: usize> Iterator for SetIter<'a, T, C> {
+impl<'a, T, H, P> IntoIterator for &'a DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = &'a T;
+    type IntoIter = SetIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_head()
+    }
+}
+
+impl<T, H, P> Clone for DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _hash_map_internal: self._hash_map_internal.clone(),
+        }
+    }
+}
+
+impl<T, H, P> fmt::Debug for DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq + fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter_head()).finish()
+    }
+}
+
+// Sets are equal when they hold the same items, whatever their order or capacity.
+impl<T, H, P> PartialEq for DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.size() == other.size() && self.iter_head().all(|item| other.exists(item))
+    }
+}
+
+impl<T, H, P> Eq for DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+// Panics once the set is full, use try_extend to get the rejected item back instead.
+impl<T, H, P> Extend<T> for DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        if let Err(error) = self.try_extend(iter) {
+            panic!("{}", error);
+        }
+    }
+}
+
+// &a | &b, &a & &b, &a - &b and &a ^ &b build a new set with the capacity of the left operand,
+// whatever the capacity of the right one. Unlike std's operators they return a Result: a union or
+// symmetric difference that does not fit the left capacity fails with OutOfCapacityError. Use
+// union_into::<C2> and friends to choose the capacity of the result instead.
+macro_rules! impl_set_operator {
+    ($op:ident, $fn:ident, $method:ident, $into:ident) => {
+        impl<T, H, P> $op<&DynFixedHashSet<T, H, P>> for &DynFixedHashSet<T, H, P>
+        where
+            T: Hash + std::cmp::Eq + Clone,
+            H: Default + Hasher,
+            P: Probe,
+        {
+            type Output = Result<DynFixedHashSet<T, H, P>, OutOfCapacityError>;
+
+            fn $fn(self, other: &DynFixedHashSet<T, H, P>) -> Self::Output {
+                DynFixedHashSet::try_from_iter(self.capacity(), self.$method(other).cloned())
+                    .map_err(OutOfCapacityError::from)
+            }
+        }
+
+        #[cfg(feature = "nightly")]
+        impl<T, const C: usize, const C2: usize, H, P> $op<&FixedSizeHashSet<T, C2, H, P>>
+            for &FixedSizeHashSet<T, C, H, P>
+        where
+            Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+            Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+            T: Hash + std::cmp::Eq + Clone,
+            H: Default + Hasher,
+            P: Probe,
+        {
+            type Output = Result<FixedSizeHashSet<T, C, H, P>, OutOfCapacityError>;
+
+            fn $fn(self, other: &FixedSizeHashSet<T, C2, H, P>) -> Self::Output {
+                self.$into::<C>(other)
+            }
+        }
+    };
+}
+
+impl_set_operator!(BitOr, bitor, union, union_into);
+impl_set_operator!(BitAnd, bitand, intersection, intersection_into);
+impl_set_operator!(Sub, sub, difference, difference_into);
+impl_set_operator!(BitXor, bitxor, symmetric_difference, symmetric_difference_into);
+
+// Compile time checked capacity. All set operations are provided by the DynFixedHashSet it
+// dereferences to.
+#[cfg(feature = "nightly")]
+pub struct FixedSizeHashSet<T, const C: usize, H = DefaultHasher, P = LinearProbe>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _hash_set: DynFixedHashSet<T, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Default for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    const CAPACITY: usize = C;
+    type _Hash = H;
+
+    pub fn placeholder() -> FixedSizeHashSet<T, C, H, P> {
+        FixedSizeHashSet::<T, C, H, P> {
+            _hash_set: DynFixedHashSet::placeholder(C),
+        }
+    }
+
+    pub fn new() -> FixedSizeHashSet<T, C, H, P> {
+        FixedSizeHashSet::<T, C, H, P> {
+            _hash_set: DynFixedHashSet::new(C),
+        }
+    }
+
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, InsertError<T, ()>>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Ok(Self {
+            _hash_set: DynFixedHashSet::try_from_iter(C, iter)?,
+        })
+    }
+
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+
+    // Set algebra into a new set of capacity C2, which may be smaller or larger than C.
+    pub fn union_into<const C2: usize>(
+        &self,
+        other: &DynFixedHashSet<T, H, P>,
+    ) -> Result<FixedSizeHashSet<T, C2, H, P>, OutOfCapacityError>
+    where
+        Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+        T: Clone,
+    {
+        FixedSizeHashSet::try_from_iter(self.union(other).cloned()).map_err(OutOfCapacityError::from)
+    }
+
+    pub fn intersection_into<const C2: usize>(
+        &self,
+        other: &DynFixedHashSet<T, H, P>,
+    ) -> Result<FixedSizeHashSet<T, C2, H, P>, OutOfCapacityError>
+    where
+        Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+        T: Clone,
+    {
+        FixedSizeHashSet::try_from_iter(self.intersection(other).cloned())
+            .map_err(OutOfCapacityError::from)
+    }
+
+    pub fn difference_into<const C2: usize>(
+        &self,
+        other: &DynFixedHashSet<T, H, P>,
+    ) -> Result<FixedSizeHashSet<T, C2, H, P>, OutOfCapacityError>
+    where
+        Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+        T: Clone,
+    {
+        FixedSizeHashSet::try_from_iter(self.difference(other).cloned())
+            .map_err(OutOfCapacityError::from)
+    }
+
+    pub fn symmetric_difference_into<const C2: usize>(
+        &self,
+        other: &DynFixedHashSet<T, H, P>,
+    ) -> Result<FixedSizeHashSet<T, C2, H, P>, OutOfCapacityError>
+    where
+        Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+        T: Clone,
+    {
+        FixedSizeHashSet::try_from_iter(self.symmetric_difference(other).cloned())
+            .map_err(OutOfCapacityError::from)
+    }
+
+    pub fn grow_into<const C2: usize>(self) -> FixedSizeHashSet<T, C2, H, P>
+    where
+        Check<{ is_prime_and_within_limit(C2, crate::MAX_CAPACITY) }>: IsTrue,
+        Check<{ C2 >= C }>: IsTrue,
+    {
+        let mut hash_set = self._hash_set;
+        hash_set.grow(C2);
+        FixedSizeHashSet {
+            _hash_set: hash_set,
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Deref for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynFixedHashSet<T, H, P>;
+    fn deref(&self) -> &Self::Target {
+        &self._hash_set
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> DerefMut for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._hash_set
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> IntoIterator for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = T;
+    type IntoIter = SetIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self._hash_set.into_iter()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, T, const C: usize, H, P> IntoIterator for &'a FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = &'a T;
+    type IntoIter = SetIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self._hash_set.iter_head()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Clone for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq + Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _hash_set: self._hash_set.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> fmt::Debug for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq + fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self._hash_set.fmt(f)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> PartialEq for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self._hash_set == other._hash_set
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Eq for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Extend<T> for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self._hash_set.extend(iter);
+    }
+}
+
+pub struct SetIter<'a, T: 'a> {
+    _inner_iter: MapIteratorImpl<'a, T, (), MapEntry<T, ()>>,
+}
+
+impl<'a, T: 'a> Iterator for SetIter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -122,3 +839,246 @@ impl<'a, T: 'a, const C: usize> Iterator for SetIter<'a, T, C> {
         self._inner_iter.count()
     }
 }
+
+impl<'a, T: 'a> DoubleEndedIterator for SetIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| e.key())
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for SetIter<'a, T> {}
+
+impl<'a, T: 'a> FusedIterator for SetIter<'a, T> {}
+
+pub struct SetExtractIf<'a, T: 'a, H, P, F>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+    F: FnMut(&T) -> bool,
+{
+    _inner_iter: ExtractIfImpl<'a, T, (), H, MapEntry<T, ()>, P>,
+    _pred: F,
+}
+
+impl<'a, T: 'a, H, P, F> Iterator for SetExtractIf<'a, T, H, P, F>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+    F: FnMut(&T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pred = &mut self._pred;
+        self._inner_iter
+            .next_matching(|e| pred(e.key()))
+            .map(|e| e.into_key_value().0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, T: 'a, H, P, F> FusedIterator for SetExtractIf<'a, T, H, P, F>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+    F: FnMut(&T) -> bool,
+{
+}
+
+pub struct SetIntoIter<T> {
+    _inner_iter: IntoIteratorImpl<T, (), MapEntry<T, ()>>,
+}
+
+impl<T> Iterator for SetIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.into_key_value().0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for SetIntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next_back().map(|e| e.into_key_value().0)
+    }
+}
+
+impl<T> ExactSizeIterator for SetIntoIter<T> {}
+
+impl<T> FusedIterator for SetIntoIter<T> {}
+
+pub struct SetDrain<'a, T: 'a> {
+    _inner_iter: DrainImpl<'a, T, (), MapEntry<T, ()>>,
+}
+
+impl<'a, T: 'a> Iterator for SetDrain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next().map(|e| e.into_key_value().0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for SetDrain<'a, T> {}
+
+impl<'a, T: 'a> FusedIterator for SetDrain<'a, T> {}
+
+pub struct SetUnion<'a, T: 'a, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _left: SetIter<'a, T>,
+    _right_only: SetDifference<'a, T, H, P>,
+}
+
+impl<'a, T: 'a, H, P> Iterator for SetUnion<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._left.next().or_else(|| self._right_only.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self._left.len();
+        let (_, right) = self._right_only.size_hint();
+        (left, right.map(|right| left + right))
+    }
+}
+
+impl<'a, T: 'a, H, P> FusedIterator for SetUnion<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+pub struct SetIntersection<'a, T: 'a, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _iter: SetIter<'a, T>,
+    _other: &'a DynFixedHashSet<T, H, P>,
+}
+
+impl<'a, T: 'a, H, P> Iterator for SetIntersection<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self._other;
+        self._iter.find(|item| other.exists(*item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self._iter.len()))
+    }
+}
+
+impl<'a, T: 'a, H, P> FusedIterator for SetIntersection<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+pub struct SetDifference<'a, T: 'a, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _iter: SetIter<'a, T>,
+    _other: &'a DynFixedHashSet<T, H, P>,
+}
+
+impl<'a, T: 'a, H, P> Iterator for SetDifference<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self._other;
+        self._iter.find(|item| !other.exists(*item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self._iter.len()))
+    }
+}
+
+impl<'a, T: 'a, H, P> FusedIterator for SetDifference<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
+
+pub struct SetSymmetricDifference<'a, T: 'a, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _left_only: SetDifference<'a, T, H, P>,
+    _right_only: SetDifference<'a, T, H, P>,
+}
+
+impl<'a, T: 'a, H, P> Iterator for SetSymmetricDifference<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._left_only.next().or_else(|| self._right_only.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, left) = self._left_only.size_hint();
+        let (_, right) = self._right_only.size_hint();
+        (0, left.zip(right).map(|(left, right)| left + right))
+    }
+}
+
+impl<'a, T: 'a, H, P> FusedIterator for SetSymmetricDifference<'a, T, H, P>
+where
+    T: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+}
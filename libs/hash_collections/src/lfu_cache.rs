@@ -0,0 +1,371 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::hash::{DefaultHasher, Hash, Hasher};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_nonzero_and_within_limit};
+#[cfg(feature = "nightly")]
+use crate::eviction::forward_eviction_policy;
+use crate::{
+    eviction::{CacheEntry, EvictionPolicy, cache_table_capacity},
+    hash_map::DynFixedHashMap,
+    hash_map_internal::{Entry, FixedSizeHashMapImpl, SlotLookup},
+    probe::LinearProbe,
+};
+
+// The list is kept sorted by access count, and by recency within entries of the same count, so
+// the head is always the entry to evict. Each run of entries with the same count forms a
+// frequency bucket; only the last slot of every bucket is tracked, the links do the rest.
+// Probing is fixed to linear so entries never move behind the bucket tails' back.
+pub struct DynLfuCache<K, V, H = DefaultHasher>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    _hash_map_internal: FixedSizeHashMapImpl<K, V, H, CacheEntry<K, V>, LinearProbe>,
+    _bucket_tails: DynFixedHashMap<u32, usize>,
+    _capacity: usize,
+    _on_evict: Option<Box<dyn FnMut(K, V)>>,
+}
+
+impl<K, V, H> DynLfuCache<K, V, H>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    pub fn new(capacity: usize) -> DynLfuCache<K, V, H> {
+        DynLfuCache::<K, V, H> {
+            _hash_map_internal: FixedSizeHashMapImpl::new(cache_table_capacity(capacity)),
+            _bucket_tails: DynFixedHashMap::new(cache_table_capacity(capacity)),
+            _capacity: capacity,
+            _on_evict: None,
+        }
+    }
+
+    pub fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F) {
+        self._on_evict = Some(Box::new(callback));
+    }
+
+    // Inserting counts as a use. Updating an existing entry counts as another one.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self._hash_map_internal.hash_of(&key);
+        let mut slot = self._hash_map_internal.find_slot(hash, &key);
+        if let SlotLookup::Occupied(index) = slot {
+            self._record_use(index);
+            return self
+                ._hash_map_internal
+                .get_mut_entry_at(index)
+                .map(|e| std::mem::replace(e.mut_value(), value));
+        }
+
+        if self.size() >= self._capacity {
+            while self.size() >= self._capacity {
+                let Some((lfu_key, lfu_value)) = self.evict() else {
+                    // A zero capacity cache evicts everything straight away.
+                    self._evicted(key, value);
+                    return None;
+                };
+                self._evicted(lfu_key, lfu_value);
+            }
+            // The vacant slot found above may be gone after evicting.
+            slot = self._hash_map_internal.find_slot(hash, &key);
+        }
+
+        let SlotLookup::Vacant(index) = slot else {
+            unreachable!("key was looked up above");
+        };
+        match self._hash_map_internal.insert_at(index, hash, key, value) {
+            Ok(entry) => *entry.mut_uses() = 1,
+            Err(error) => {
+                let (key, value) = error.into_inner();
+                self._evicted(key, value);
+                return None;
+            }
+        }
+
+        let after = self
+            ._bucket_tails
+            .get(&1)
+            .copied()
+            .unwrap_or(self._hash_map_internal.capacity());
+        self._hash_map_internal.move_after(index, after);
+        let _ = self._bucket_tails.insert(1, index);
+        None
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(key)?;
+        self._record_use(index);
+        self._hash_map_internal.get_entry_at(index).map(|e| e.value())
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(key)?;
+        self._record_use(index);
+        self._hash_map_internal.get_mut_entry_at(index).map(|e| e.mut_value())
+    }
+
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .get_entry_and_index_of(key)
+            .map(|e| e.0.value())
+    }
+
+    // Number of recorded uses of the entry, 0 if it is not cached.
+    pub fn frequency<Q>(&self, key: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .get_entry_and_index_of(key)
+            .map_or(0, |e| e.0.uses())
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(key)?;
+        self._take_at(index).map(|e| e.consume_self())
+    }
+
+    // Removes the least frequently used entry, the least recently used one among equals.
+    pub fn evict(&mut self) -> Option<(K, V)> {
+        let head = self._hash_map_internal.head_index();
+        self._take_at(head).map(|e| e.into_key_value())
+    }
+
+    pub fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal.exists(key)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._capacity
+    }
+
+    pub fn size(&self) -> usize {
+        self._hash_map_internal.size()
+    }
+
+    // Moves entry i from the end of its bucket to the end of the next bucket up. The count
+    // saturates: an entry at u32::MAX uses stays where it is.
+    fn _record_use(&mut self, i: usize) {
+        let Some(uses) = self._hash_map_internal.get_entry_at(i).map(|e| e.uses()) else {
+            return;
+        };
+        if uses == u32::MAX {
+            return;
+        }
+
+        let after = match self._bucket_tails.get(&(uses + 1)) {
+            Some(next_bucket_tail) => *next_bucket_tail,
+            None => self._bucket_tails.get(&uses).copied().unwrap_or(i),
+        };
+        self._leave_bucket(i, uses);
+        self._hash_map_internal.move_after(i, after);
+
+        if let Some(entry) = self._hash_map_internal.get_mut_entry_at(i) {
+            *entry.mut_uses() = uses + 1;
+        }
+        let _ = self._bucket_tails.insert(uses + 1, i);
+    }
+
+    fn _leave_bucket(&mut self, i: usize, uses: u32) {
+        if self._bucket_tails.get(&uses) != Some(&i) {
+            return;
+        }
+
+        let prev = self._hash_map_internal.get_entry_at(i).map(|e| e.prev());
+        match prev.and_then(|prev| self._hash_map_internal.get_entry_at(prev).map(|e| (prev, e))) {
+            Some((prev, prev_entry)) if prev_entry.uses() == uses => {
+                let _ = self._bucket_tails.insert(uses, prev);
+            }
+            _ => {
+                self._bucket_tails.remove(&uses);
+            }
+        }
+    }
+
+    fn _take_at(&mut self, i: usize) -> Option<CacheEntry<K, V>> {
+        let uses = self._hash_map_internal.get_entry_at(i)?.uses();
+        self._leave_bucket(i, uses);
+        let entry = self._hash_map_internal.take_at(i);
+
+        if self._hash_map_internal.needs_compaction() {
+            self._hash_map_internal.compact();
+            self._rebuild_bucket_tails();
+        }
+        entry
+    }
+
+    // Compaction moves entries to new slots, the bucket tails are read off the list again.
+    fn _rebuild_bucket_tails(&mut self) {
+        let mut bucket_tails = DynFixedHashMap::new(self._bucket_tails.capacity());
+        let mut i = self._hash_map_internal.head_index();
+        while let Some(entry) = self._hash_map_internal.get_entry_at(i) {
+            let _ = bucket_tails.insert(entry.uses(), i);
+            i = entry.next();
+        }
+        self._bucket_tails = bucket_tails;
+    }
+
+    fn _evicted(&mut self, key: K, value: V) {
+        if let Some(callback) = self._on_evict.as_mut() {
+            callback(key, value);
+        }
+    }
+}
+
+impl<K, V, H> EvictionPolicy<K, V> for DynLfuCache<K, V, H>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.get(key)
+    }
+
+    fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.peek(key)
+    }
+
+    fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.remove(key)
+    }
+
+    fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.exists(key)
+    }
+
+    fn evict(&mut self) -> Option<(K, V)> {
+        self.evict()
+    }
+
+    fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F) {
+        self.set_eviction_callback(callback)
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+}
+
+#[cfg(feature = "nightly")]
+pub struct LfuCache<K, V, const C: usize, H = DefaultHasher>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    _cache: DynLfuCache<K, V, H>,
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Default for LfuCache<K, V, C, H>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> LfuCache<K, V, C, H>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    pub fn new() -> LfuCache<K, V, C, H> {
+        LfuCache::<K, V, C, H> {
+            _cache: DynLfuCache::new(C),
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        C
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Deref for LfuCache<K, V, C, H>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    type Target = DynLfuCache<K, V, H>;
+    fn deref(&self) -> &Self::Target {
+        &self._cache
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> DerefMut for LfuCache<K, V, C, H>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._cache
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> EvictionPolicy<K, V> for LfuCache<K, V, C, H>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+{
+    forward_eviction_policy!();
+}
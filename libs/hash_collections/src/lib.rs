@@ -1,24 +1,71 @@
-#![allow(incomplete_features)]
-#![allow(internal_features)]
-#![feature(adt_const_params)]
-#![feature(const_type_id)]
-#![feature(generic_const_exprs)]
-#![feature(core_intrinsics)]
-#![feature(inherent_associated_types)]
+#![cfg_attr(feature = "nightly", allow(incomplete_features))]
+#![cfg_attr(feature = "nightly", allow(internal_features))]
+#![cfg_attr(feature = "nightly", feature(adt_const_params))]
+#![cfg_attr(feature = "nightly", feature(generic_const_exprs))]
+#![cfg_attr(feature = "nightly", feature(inherent_associated_types))]
 
 mod check;
 mod hash_map_internal;
+#[cfg(feature = "serde")]
+mod serde_support;
 
+pub mod bi_map;
+pub mod clock_cache;
+pub mod counter;
 pub mod hash_graph;
 pub mod hash_map;
 pub mod hash_set;
+pub mod lfu_cache;
+pub mod lru_cache;
+pub mod multi_map;
+pub mod probe;
+pub mod snapshot;
+pub mod ttl_map;
 pub mod errors;
+pub mod eviction;
 
-const MAX_CAPACITY: usize = 50849;
+// Compile time capacity checks use const_primes::is_prime, a deterministic Miller-Rabin test, so
+// the ceiling only guards against accidentally huge allocations, from const capacities and from
+// snapshot headers alike. The "unbounded_capacity" feature lifts it entirely.
+#[cfg(not(feature = "unbounded_capacity"))]
+const MAX_CAPACITY: usize = 100_000_000;
+#[cfg(feature = "unbounded_capacity")]
+const MAX_CAPACITY: usize = usize::MAX;
 
+#[cfg(feature = "nightly")]
+pub use crate::bi_map::FixedSizeBiMap;
+#[cfg(feature = "nightly")]
+pub use crate::counter::FixedSizeCounter;
+#[cfg(feature = "nightly")]
 pub use crate::hash_graph::FixedSizeHashGraphMap;
+#[cfg(feature = "nightly")]
 pub use crate::hash_map::FixedSizeHashMap;
+#[cfg(feature = "nightly")]
 pub use crate::hash_set::FixedSizeHashSet;
-pub use crate::errors::OutOfCapacityError;
+#[cfg(feature = "nightly")]
+pub use crate::lru_cache::LruCache;
+#[cfg(feature = "nightly")]
+pub use crate::multi_map::FixedSizeMultiMap;
+#[cfg(feature = "nightly")]
+pub use crate::lfu_cache::LfuCache;
+#[cfg(feature = "nightly")]
+pub use crate::clock_cache::ClockCache;
+#[cfg(feature = "nightly")]
+pub use crate::ttl_map::TtlHashMap;
+pub use crate::bi_map::DynFixedBiMap;
+pub use crate::counter::DynFixedCounter;
+pub use crate::hash_graph::DynFixedHashGraphMap;
+pub use crate::hash_map::DynFixedHashMap;
+pub use crate::hash_set::DynFixedHashSet;
+pub use crate::lru_cache::DynLruCache;
+pub use crate::multi_map::DynFixedMultiMap;
+pub use crate::lfu_cache::DynLfuCache;
+pub use crate::clock_cache::DynClockCache;
+pub use crate::eviction::EvictionPolicy;
+pub use crate::ttl_map::{Clock, DynTtlHashMap, SystemClock};
+pub use crate::snapshot::{Pod, SnapshotCodec, SnapshotView};
+pub use crate::errors::{
+    BiMapInsertError, GraphInsertError, InsertError, OutOfCapacityError, SnapshotError,
+};
 
 mod unittests;
@@ -0,0 +1,321 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::hash::{DefaultHasher, Hash, Hasher};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_nonzero_and_within_limit};
+#[cfg(feature = "nightly")]
+use crate::eviction::forward_eviction_policy;
+use crate::{
+    eviction::{EvictionPolicy, cache_table_capacity},
+    hash_map::{MapIter, MapImpl},
+    hash_map_internal::{Entry, FixedSizeHashMapImpl, SlotLookup},
+    probe::{LinearProbe, Probe},
+};
+
+// Least recently used entry sits at the head of the list, most recently used at the tail.
+pub struct DynLruCache<K, V, H = DefaultHasher, P = LinearProbe>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _hash_map_internal: MapImpl<K, V, H, P>,
+    _capacity: usize,
+    _on_evict: Option<Box<dyn FnMut(K, V)>>,
+}
+
+impl<K, V, H, P> DynLruCache<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new(capacity: usize) -> DynLruCache<K, V, H, P> {
+        DynLruCache::<K, V, H, P> {
+            _hash_map_internal: FixedSizeHashMapImpl::new(cache_table_capacity(capacity)),
+            _capacity: capacity,
+            _on_evict: None,
+        }
+    }
+
+    // Called with every entry that insert pushes out of the cache. Entries removed through
+    // remove or pop_lru are returned to the caller instead.
+    pub fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F) {
+        self._on_evict = Some(Box::new(callback));
+    }
+
+    // Inserts or updates the entry and makes it the most recently used one. Evicts the least
+    // recently used entry when the cache is full.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self._hash_map_internal.hash_of(&key);
+        let mut slot = self._hash_map_internal.find_slot(hash, &key);
+        if let SlotLookup::Occupied(index) = slot {
+            let old_value = self
+                ._hash_map_internal
+                .get_mut_entry_at(index)
+                .map(|e| std::mem::replace(e.mut_value(), value));
+            self._hash_map_internal.move_to_back(index);
+            return old_value;
+        }
+
+        if self.size() >= self._capacity {
+            while self.size() >= self._capacity {
+                let Some((lru_key, lru_value)) = self.pop_lru() else {
+                    // A zero capacity cache evicts everything straight away.
+                    self._evicted(key, value);
+                    return None;
+                };
+                self._evicted(lru_key, lru_value);
+            }
+            // Evicting may compact the table, which moves the free slots.
+            slot = self._hash_map_internal.find_slot(hash, &key);
+        }
+
+        let SlotLookup::Vacant(index) = slot else {
+            unreachable!("key was looked up above");
+        };
+        if let Err(error) = self._hash_map_internal.insert_at(index, hash, key, value) {
+            let (key, value) = error.into_inner();
+            self._evicted(key, value);
+        }
+        None
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(key)?;
+        self._hash_map_internal.move_to_back(index);
+        self._hash_map_internal.get_entry_at(index).map(|e| e.value())
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let index = self._hash_map_internal.get_index_of(key)?;
+        self._hash_map_internal.move_to_back(index);
+        self._hash_map_internal.get_mut_entry_at(index).map(|e| e.mut_value())
+    }
+
+    // Looks the entry up without changing its position.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .get_entry_and_index_of(key)
+            .map(|e| e.0.value())
+    }
+
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        self._hash_map_internal.head().map(|e| (e.key(), e.value()))
+    }
+
+    pub fn promote<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        match self._hash_map_internal.get_index_of(key) {
+            Some(index) => {
+                self._hash_map_internal.move_to_back(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        self._hash_map_internal
+            .pop_head()
+            .map(|e| e.into_key_value())
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal
+            .remove(key)
+            .map(|e| e.consume_self())
+    }
+
+    pub fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._hash_map_internal.exists(key)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._capacity
+    }
+
+    pub fn size(&self) -> usize {
+        self._hash_map_internal.size()
+    }
+
+    // From the least to the most recently used entry.
+    pub fn iter(&self) -> MapIter<'_, K, V> {
+        MapIter {
+            _inner_iter: self._hash_map_internal.iter_head(),
+        }
+    }
+
+    fn _evicted(&mut self, key: K, value: V) {
+        if let Some(callback) = self._on_evict.as_mut() {
+            callback(key, value);
+        }
+    }
+}
+
+impl<K, V, H, P> EvictionPolicy<K, V> for DynLruCache<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.get(key)
+    }
+
+    fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.peek(key)
+    }
+
+    fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.remove(key)
+    }
+
+    fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.exists(key)
+    }
+
+    fn evict(&mut self) -> Option<(K, V)> {
+        self.pop_lru()
+    }
+
+    fn set_eviction_callback<F: FnMut(K, V) + 'static>(&mut self, callback: F) {
+        self.set_eviction_callback(callback)
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity()
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+}
+
+#[cfg(feature = "nightly")]
+pub struct LruCache<K, V, const C: usize, H = DefaultHasher, P = LinearProbe>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _cache: DynLruCache<K, V, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Default for LruCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> LruCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new() -> LruCache<K, V, C, H, P> {
+        LruCache::<K, V, C, H, P> {
+            _cache: DynLruCache::new(C),
+        }
+    }
+
+    pub const fn capacity(&self) -> usize {
+        C
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Deref for LruCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynLruCache<K, V, H, P>;
+    fn deref(&self) -> &Self::Target {
+        &self._cache
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> DerefMut for LruCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._cache
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> EvictionPolicy<K, V> for LruCache<K, V, C, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    forward_eviction_policy!();
+}
@@ -0,0 +1,520 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::iter::FusedIterator;
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
+
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_prime_and_within_limit};
+use crate::{
+    hash_map::{DynFixedHashMap, MapIter, MapKeys},
+    probe::{LinearProbe, Probe},
+    errors::InsertError,
+};
+
+// The values of one key, chained through the pool in insertion order.
+#[derive(Clone, Debug)]
+struct ValueList {
+    _head: usize,
+    _tail: usize,
+    _len: usize,
+}
+
+// A pool slot either holds a value and the next value of the same key, or is free and points at
+// the next free slot.
+#[derive(Clone, Debug)]
+struct PoolEntry<V> {
+    _value: Option<V>,
+    _next: usize,
+}
+
+// Maps each key to any number of values. All values share one pool with as many slots as the
+// key table, so the capacity bounds the total number of values. Every key holds at least one
+// value, so the key table can never fill up before the pool does.
+pub struct DynFixedMultiMap<K, V, H = DefaultHasher, P = LinearProbe>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _keys: DynFixedHashMap<K, ValueList, H, P>,
+    _pool: Vec<PoolEntry<V>>,
+    _free: usize,
+    _size: usize,
+}
+
+impl<K, V, H, P> DynFixedMultiMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new(capacity: usize) -> DynFixedMultiMap<K, V, H, P> {
+        let keys = DynFixedHashMap::new(capacity);
+        let capacity = keys.capacity();
+        DynFixedMultiMap {
+            _keys: keys,
+            _pool: Self::_free_pool(capacity),
+            _free: 0,
+            _size: 0,
+        }
+    }
+
+    fn _free_pool(capacity: usize) -> Vec<PoolEntry<V>> {
+        (0..capacity)
+            .map(|i| PoolEntry {
+                _value: None,
+                _next: i + 1,
+            })
+            .collect()
+    }
+
+    // Puts the value of slot i back on the free list.
+    fn _release(&mut self, i: usize) -> Option<V> {
+        let value = self._pool[i]._value.take();
+        self._pool[i]._next = self._free;
+        self._free = i;
+        self._size -= 1;
+        value
+    }
+
+    fn _values_of(&self, list: Option<&ValueList>) -> MultiMapValues<'_, V> {
+        MultiMapValues {
+            _pool: &self._pool,
+            _next: list.map_or(self._pool.len(), |list| list._head),
+            _remaining: list.map_or(0, |list| list._len),
+        }
+    }
+
+    // Appends the value to the values of key. Fails once the pool is full.
+    pub fn insert(&mut self, key: K, value: V) -> Result<(), InsertError<K, V>> {
+        let end = self._pool.len();
+        let slot = self._free;
+        if slot == end {
+            return Err(InsertError {
+                capacity: self.capacity(),
+                key,
+                value,
+            });
+        }
+
+        match self._keys.get_mut(&key) {
+            Some(list) => {
+                self._pool[list._tail]._next = slot;
+                list._tail = slot;
+                list._len += 1;
+            }
+            None => {
+                let list = ValueList {
+                    _head: slot,
+                    _tail: slot,
+                    _len: 1,
+                };
+                if let Err(error) = self._keys.insert(key, list) {
+                    return Err(InsertError {
+                        capacity: error.capacity,
+                        key: error.key,
+                        value,
+                    });
+                }
+            }
+        }
+
+        self._free = self._pool[slot]._next;
+        self._pool[slot] = PoolEntry {
+            _value: Some(value),
+            _next: end,
+        };
+        self._size += 1;
+        Ok(())
+    }
+
+    // The values of key in the order they were inserted.
+    pub fn get_all<Q>(&self, key: &Q) -> MultiMapValues<'_, V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._values_of(self._keys.get(key))
+    }
+
+    pub fn len_of<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._keys.get(key).map_or(0, |list| list._len)
+    }
+
+    pub fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self._keys.exists(key)
+    }
+
+    // Removes the first value of key equal to value. The key goes with its last value.
+    pub fn remove_one<Q>(&mut self, key: &Q, value: &V) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+        V: PartialEq,
+    {
+        let end = self._pool.len();
+        let list = self._keys.get_mut(key)?;
+
+        let mut prev = end;
+        let mut i = list._head;
+        while i != end && self._pool[i]._value.as_ref() != Some(value) {
+            prev = i;
+            i = self._pool[i]._next;
+        }
+        if i == end {
+            return None;
+        }
+
+        let next = self._pool[i]._next;
+        if prev == end {
+            list._head = next;
+        } else {
+            self._pool[prev]._next = next;
+        }
+        if list._tail == i {
+            list._tail = prev;
+        }
+        list._len -= 1;
+        if list._len == 0 {
+            self._keys.remove(key);
+        }
+        self._release(i)
+    }
+
+    // Removes key and hands back all its values in insertion order.
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Vec<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let Some(list) = self._keys.remove(key) else {
+            return Vec::new();
+        };
+
+        let mut values = Vec::with_capacity(list._len);
+        let mut i = list._head;
+        for _ in 0..list._len {
+            let next = self._pool[i]._next;
+            values.extend(self._release(i));
+            i = next;
+        }
+        values
+    }
+
+    // Inserts pairs until one does not fit. Pairs before it stay inserted, the ones after it
+    // are not consumed.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), InsertError<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        for (key, value) in iter {
+            self.insert(key, value)?;
+        }
+        Ok(())
+    }
+
+    // Total number of values the pool holds.
+    pub fn capacity(&self) -> usize {
+        self._pool.len()
+    }
+
+    // Total number of values over all keys.
+    pub fn size(&self) -> usize {
+        self._size
+    }
+
+    pub fn key_count(&self) -> usize {
+        self._keys.size()
+    }
+
+    // Keys in the order they were first inserted.
+    pub fn keys(&self) -> MultiMapKeys<'_, K> {
+        MultiMapKeys {
+            _inner_iter: self._keys.keys(),
+        }
+    }
+
+    // Every (key, value) pair, grouped by key in key order.
+    pub fn iter_head(&self) -> MultiMapIter<'_, K, V> {
+        MultiMapIter {
+            _keys: self._keys.iter_head(),
+            _current: None,
+            _pool: &self._pool,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self._keys.clear();
+        self._pool = Self::_free_pool(self._pool.len());
+        self._free = 0;
+        self._size = 0;
+    }
+}
+
+impl<K, V, H, P> Clone for DynFixedMultiMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + Clone,
+    V: Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _keys: self._keys.clone(),
+            _pool: self._pool.clone(),
+            _free: self._free,
+            _size: self._size,
+        }
+    }
+}
+
+// Shows every key with the list of its values.
+impl<K, V, H, P> fmt::Debug for DynFixedMultiMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + fmt::Debug,
+    V: fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(
+                self._keys
+                    .iter_head()
+                    .map(|(key, list)| (key, self._values_of(Some(list)).collect::<Vec<_>>())),
+            )
+            .finish()
+    }
+}
+
+// Panics once the pool is full, use try_extend to get the rejected pair back instead.
+impl<K, V, H, P> Extend<(K, V)> for DynFixedMultiMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        if let Err(error) = self.try_extend(iter) {
+            panic!("{}", error);
+        }
+    }
+}
+
+// Compile time checked capacity. All multimap operations are provided by the DynFixedMultiMap it
+// dereferences to.
+#[cfg(feature = "nightly")]
+pub struct FixedSizeMultiMap<K, V, const C: usize, H = DefaultHasher, P = LinearProbe>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _multi_map: DynFixedMultiMap<K, V, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Default for FixedSizeMultiMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> FixedSizeMultiMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    const CAPACITY: usize = C;
+
+    pub fn new() -> FixedSizeMultiMap<K, V, C, H, P> {
+        FixedSizeMultiMap {
+            _multi_map: DynFixedMultiMap::new(C),
+        }
+    }
+
+    pub fn try_from_iter<I>(iter: I) -> Result<Self, InsertError<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut multi_map = Self::new();
+        multi_map.try_extend(iter)?;
+        Ok(multi_map)
+    }
+
+    pub const fn capacity(&self) -> usize {
+        Self::CAPACITY
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Deref for FixedSizeMultiMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynFixedMultiMap<K, V, H, P>;
+
+    fn deref(&self) -> &Self::Target {
+        &self._multi_map
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> DerefMut for FixedSizeMultiMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._multi_map
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Clone for FixedSizeMultiMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Clone,
+    V: Clone,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn clone(&self) -> Self {
+        Self {
+            _multi_map: self._multi_map.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> fmt::Debug for FixedSizeMultiMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + fmt::Debug,
+    V: fmt::Debug,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self._multi_map, f)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Extend<(K, V)> for FixedSizeMultiMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self._multi_map.extend(iter);
+    }
+}
+
+pub struct MultiMapValues<'a, V: 'a> {
+    _pool: &'a [PoolEntry<V>],
+    _next: usize,
+    _remaining: usize,
+}
+
+impl<'a, V: 'a> Iterator for MultiMapValues<'a, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let entry = &self._pool[self._next];
+        self._next = entry._next;
+        self._remaining -= 1;
+        entry._value.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self._remaining, Some(self._remaining))
+    }
+}
+
+impl<'a, V: 'a> ExactSizeIterator for MultiMapValues<'a, V> {}
+
+impl<'a, V: 'a> FusedIterator for MultiMapValues<'a, V> {}
+
+pub struct MultiMapKeys<'a, K: 'a> {
+    _inner_iter: MapKeys<'a, K, ValueList>,
+}
+
+impl<'a, K: 'a> Iterator for MultiMapKeys<'a, K> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self._inner_iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self._inner_iter.size_hint()
+    }
+}
+
+impl<'a, K: 'a> ExactSizeIterator for MultiMapKeys<'a, K> {}
+
+impl<'a, K: 'a> FusedIterator for MultiMapKeys<'a, K> {}
+
+pub struct MultiMapIter<'a, K: 'a, V: 'a> {
+    _keys: MapIter<'a, K, ValueList>,
+    _current: Option<(&'a K, MultiMapValues<'a, V>)>,
+    _pool: &'a [PoolEntry<V>],
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for MultiMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, values)) = &mut self._current
+                && let Some(value) = values.next()
+            {
+                return Some((*key, value));
+            }
+            let (key, list) = self._keys.next()?;
+            self._current = Some((
+                key,
+                MultiMapValues {
+                    _pool: self._pool,
+                    _next: list._head,
+                    _remaining: list._len,
+                },
+            ));
+        }
+    }
+}
+
+impl<'a, K: 'a, V: 'a> FusedIterator for MultiMapIter<'a, K, V> {}
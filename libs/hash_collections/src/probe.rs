@@ -0,0 +1,98 @@
+pub trait Probe {
+    // Robin Hood tables keep entries ordered by displacement, which lets lookups stop early and
+    // makes insertion shift poorer entries further along the probe sequence.
+    const ROBIN_HOOD: bool = false;
+
+    // Identifies the probe in snapshot headers, so it must be unique and never change.
+    const ID: u64;
+
+    fn index(hash: u64, step: usize, capacity: usize) -> usize;
+}
+
+pub struct LinearProbe;
+
+impl Probe for LinearProbe {
+    const ID: u64 = 1;
+
+    fn index(hash: u64, step: usize, capacity: usize) -> usize {
+        ((hash % capacity as u64) as usize + step) % capacity
+    }
+}
+
+// Alternates +1, -n, +4, -4n, +9, -9n, ... around the home slot, where n is a quadratic
+// non-residue of the prime capacity C. The squares reach every residue and n times the squares
+// every non-residue, so the sequence visits every slot. With C % 4 == 3, n is -1 and this is
+// the classic +k², -k² sequence.
+pub struct QuadraticProbe;
+
+impl Probe for QuadraticProbe {
+    const ID: u64 = 2;
+
+    fn index(hash: u64, step: usize, capacity: usize) -> usize {
+        let capacity = capacity as u64;
+        let home = hash % capacity;
+        let k = step.div_ceil(2) as u64 % capacity;
+        let mut offset = mul_mod(k, k, capacity);
+        if step.is_multiple_of(2) && offset != 0 {
+            offset = mul_mod(offset, non_residue(capacity), capacity);
+        }
+        ((home + offset) % capacity) as usize
+    }
+}
+
+fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+// The smallest quadratic non-residue of the odd prime p by Euler's criterion. It is tiny for
+// every prime, so the search ends after a handful of candidates.
+fn non_residue(p: u64) -> u64 {
+    if p % 4 == 3 || p < 5 {
+        return p - 1;
+    }
+    let is_non_residue = |n: u64| {
+        let (mut base, mut exp, mut power) = (n, (p - 1) / 2, 1);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                power = mul_mod(power, base, p);
+            }
+            base = mul_mod(base, base, p);
+            exp >>= 1;
+        }
+        power == p - 1
+    };
+    (2..p).find(|&n| is_non_residue(n)).unwrap_or(p - 1)
+}
+
+// The stride is derived from the upper part of the hash and lies in 1..C, so with a prime
+// capacity every stride is coprime with C and the sequence visits every slot.
+pub struct DoubleHashProbe;
+
+impl Probe for DoubleHashProbe {
+    const ID: u64 = 3;
+
+    fn index(hash: u64, step: usize, capacity: usize) -> usize {
+        let capacity = capacity as u64;
+        let home = hash % capacity;
+        let stride = if capacity > 1 {
+            1 + (hash / capacity) % (capacity - 1)
+        } else {
+            1
+        };
+        ((home + (step as u64 % capacity) * stride) % capacity) as usize
+    }
+}
+
+// Linear probing where an entry's displacement from its home slot bounds every lookup: a search
+// stops as soon as it meets an entry that is closer to home than the searched key would be.
+// Removals shift the following entries back instead of leaving tombstones.
+pub struct RobinHoodProbe;
+
+impl Probe for RobinHoodProbe {
+    const ROBIN_HOOD: bool = true;
+    const ID: u64 = 4;
+
+    fn index(hash: u64, step: usize, capacity: usize) -> usize {
+        LinearProbe::index(hash, step, capacity)
+    }
+}
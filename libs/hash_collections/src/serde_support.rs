@@ -0,0 +1,281 @@
+// Serialize/Deserialize behind the "serde" feature. Maps serialize as maps and sets as
+// sequences, both in insertion order. Graphs serialize as a sequence of
+// (key, value, [(to_key, weight), ...]) in insertion order, so edges are stored by key and not
+// by slot. Deserializing more entries than fit fails with the OutOfCapacityError message.
+//
+// Only the compile time checked collections can be deserialized, the Dyn* ones have no
+// capacity to deserialize into.
+
+use std::hash::{Hash, Hasher};
+
+use serde::ser::{Serialize, SerializeSeq, SerializeTuple, Serializer};
+
+use crate::{
+    hash_graph::{DynFixedHashGraphImpl, Node},
+    hash_map::DynFixedHashMap,
+    hash_set::DynFixedHashSet,
+    probe::Probe,
+};
+
+#[cfg(feature = "nightly")]
+use std::{fmt, marker::PhantomData};
+
+#[cfg(feature = "nightly")]
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+
+#[cfg(feature = "nightly")]
+use crate::{
+    check::{Check, IsTrue, is_prime_and_within_limit},
+    errors::OutOfCapacityError,
+    hash_graph::FixedSizeHashGraphImpl,
+    hash_map::FixedSizeHashMap,
+    hash_set::FixedSizeHashSet,
+};
+
+impl<K, V, H, P> Serialize for DynFixedHashMap<K, V, H, P>
+where
+    K: Hash + std::cmp::Eq + Serialize,
+    V: Serialize,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter_head())
+    }
+}
+
+impl<T, H, P> Serialize for DynFixedHashSet<T, H, P>
+where
+    T: Hash + std::cmp::Eq + Serialize,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter_head())
+    }
+}
+
+struct SerializeNode<'a, K, V, H>(Node<'a, K, V, H>)
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher;
+
+struct SerializeOutEdges<'a, K, V, H>(&'a Node<'a, K, V, H>)
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher;
+
+impl<K, V, H> Serialize for SerializeNode<'_, K, V, H>
+where
+    K: Hash + std::cmp::Eq + Serialize,
+    V: Serialize,
+    H: Default + Hasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(self.0.key())?;
+        tuple.serialize_element(self.0.value())?;
+        tuple.serialize_element(&SerializeOutEdges(&self.0))?;
+        tuple.end()
+    }
+}
+
+impl<K, V, H> Serialize for SerializeOutEdges<'_, K, V, H>
+where
+    K: Hash + std::cmp::Eq + Serialize,
+    H: Default + Hasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let edges = self.0.iter_out_edges();
+        let mut seq = serializer.serialize_seq(Some(edges.size_hint().0))?;
+        for (to_node, weight) in edges {
+            seq.serialize_element(&(to_node.key(), weight))?;
+        }
+        seq.end()
+    }
+}
+
+impl<K, V, H> Serialize for DynFixedHashGraphImpl<K, V, H>
+where
+    K: Hash + std::cmp::Eq + Serialize,
+    V: Serialize,
+    H: Default + Hasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter_nodes().map(SerializeNode))
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H, P> Serialize for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Serialize,
+    V: Serialize,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<T, const C: usize, H, P> Serialize for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq + Serialize,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, H> Serialize for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Serialize,
+    V: Serialize,
+    H: Default + Hasher,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "nightly")]
+struct MapVisitor<K, V, const C: usize, H, P>(PhantomData<(K, V, H, P)>);
+
+#[cfg(feature = "nightly")]
+impl<'de, K, V, const C: usize, H, P> Visitor<'de> for MapVisitor<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Deserialize<'de>,
+    V: Deserialize<'de>,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Value = FixedSizeHashMap<K, V, C, H, P>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a map with at most {} entries", C)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut map = FixedSizeHashMap::new();
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value)
+                .map_err(|error| A::Error::custom(OutOfCapacityError::from(error)))?;
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'de, K, V, const C: usize, H, P> Deserialize<'de> for FixedSizeHashMap<K, V, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Deserialize<'de>,
+    V: Deserialize<'de>,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(MapVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "nightly")]
+struct SetVisitor<T, const C: usize, H, P>(PhantomData<(T, H, P)>);
+
+#[cfg(feature = "nightly")]
+impl<'de, T, const C: usize, H, P> Visitor<'de> for SetVisitor<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq + Deserialize<'de>,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Value = FixedSizeHashSet<T, C, H, P>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence with at most {} items", C)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut set = FixedSizeHashSet::new();
+        while let Some(item) = access.next_element()? {
+            set.insert(item)
+                .map_err(|error| A::Error::custom(OutOfCapacityError::from(error)))?;
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'de, T, const C: usize, H, P> Deserialize<'de> for FixedSizeHashSet<T, C, H, P>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    T: Hash + std::cmp::Eq + Deserialize<'de>,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(SetVisitor(PhantomData))
+    }
+}
+
+#[cfg(feature = "nightly")]
+struct GraphVisitor<K, V, const C: usize, H>(PhantomData<(K, V, H)>);
+
+#[cfg(feature = "nightly")]
+impl<'de, K, V, const C: usize, H> Visitor<'de> for GraphVisitor<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Deserialize<'de>,
+    V: Deserialize<'de>,
+    H: Default + Hasher,
+{
+    type Value = FixedSizeHashGraphImpl<K, V, C, H>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of (key, value, out edges) with at most {} nodes", C)
+    }
+
+    // Edges can point at nodes that come later, so all nodes are inserted before any edge.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+        let mut graph = FixedSizeHashGraphImpl::new();
+        let mut out_edges = Vec::new();
+        while let Some((key, value, edges)) = access.next_element::<(K, V, Vec<(K, u32)>)>()? {
+            let index = graph
+                .insert_node(key, value)
+                .map_err(|error| A::Error::custom(OutOfCapacityError::from(error)))?;
+            out_edges.push((index, edges));
+        }
+
+        for (index, edges) in out_edges {
+            for (to_key, weight) in edges {
+                if !graph.connect_at_with_weight(index, &to_key, weight) {
+                    return Err(A::Error::custom("edge to a missing node or beyond the edge limit"));
+                }
+            }
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'de, K, V, const C: usize, H> Deserialize<'de> for FixedSizeHashGraphImpl<K, V, C, H>
+where
+    Check<{ is_prime_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq + Deserialize<'de>,
+    V: Deserialize<'de>,
+    H: Default + Hasher,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(GraphVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,552 @@
+// Versioned binary snapshots of the slot table. A snapshot is a fixed header followed by one
+// record per slot, so loading puts every entry back into the slot it was written from without
+// rehashing. The list order and any slot indices held by entries (graph edges) stay valid.
+//
+// Header, integers little endian:
+//   magic "HCOLSNAP", version, kind, layout, flags, key size, value size (u32 each),
+//   capacity, size, head, tail, hasher id, probe id, body length, checksum (u64 each)
+//
+// Slot record: state byte, then for occupied slots hash, next and prev (u64 each), key, value
+// and whatever the collection appends (graphs append their edge tables). The packed layout
+// encodes keys and values with SnapshotCodec and writes nothing else for free slots. The fixed
+// layout copies Pod keys and values as raw bytes and pads every record to the same stride,
+// which is what lets SnapshotView read a snapshot in place.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::marker::PhantomData;
+use std::{mem, ptr, slice};
+
+use crate::{
+    check::is_nonzero_and_within_limit,
+    errors::SnapshotError,
+    hash_map_internal::{Entry, FixedSizeHashMapImpl, Slot},
+    probe::{LinearProbe, Probe},
+};
+
+const MAGIC: &[u8; 8] = b"HCOLSNAP";
+const VERSION: u32 = 2;
+const HEADER_SIZE: usize = 8 + 6 * 4 + 8 * 8;
+const FLAG_BIG_ENDIAN: u32 = 1;
+
+pub(crate) const KIND_MAP: u32 = 0;
+pub(crate) const KIND_GRAPH: u32 = 1;
+
+const LAYOUT_PACKED: u32 = 0;
+const LAYOUT_FIXED: u32 = 1;
+
+const STATE_EMPTY: u8 = 0;
+const STATE_TOMBSTONE: u8 = 1;
+const STATE_OCCUPIED: u8 = 2;
+
+// State byte plus hash, next and prev.
+const RECORD_HEADER_SIZE: usize = 1 + 3 * 8;
+
+/// Types that are copied in and out of snapshots byte for byte, in native byte order.
+///
+/// # Safety
+///
+/// Implementors must not contain padding, pointers or references, and every bit pattern must be
+/// a valid value.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, ());
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+pub trait SnapshotCodec: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    // Reads one value off the front of input, None if input does not start with one.
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+}
+
+impl<T: Pod> SnapshotCodec for T {
+    fn encode(&self, out: &mut Vec<u8>) {
+        // SAFETY: Pod types have no padding, so all of their bytes are initialised.
+        let bytes =
+            unsafe { slice::from_raw_parts((self as *const T).cast::<u8>(), mem::size_of::<T>()) };
+        out.extend_from_slice(bytes);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let (bytes, rest) = input.split_at_checked(mem::size_of::<T>())?;
+        *input = rest;
+        // SAFETY: bytes holds size_of::<T>() bytes, any bit pattern is a valid Pod value and
+        // read_unaligned has no alignment requirement.
+        Some(unsafe { ptr::read_unaligned(bytes.as_ptr().cast::<T>()) })
+    }
+}
+
+impl SnapshotCodec for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl SnapshotCodec for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_index(out, self.len());
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = decode_index(input)?;
+        let (bytes, rest) = input.split_at_checked(len)?;
+        *input = rest;
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+// Slot indices and counts are always written as u64 little endian.
+pub(crate) fn encode_index(out: &mut Vec<u8>, index: usize) {
+    out.extend_from_slice(&(index as u64).to_le_bytes());
+}
+
+pub(crate) fn decode_index(input: &mut &[u8]) -> Option<usize> {
+    decode_u64(input).and_then(|index| usize::try_from(index).ok())
+}
+
+fn decode_u64(input: &mut &[u8]) -> Option<u64> {
+    let (bytes, rest) = input.split_first_chunk::<8>()?;
+    *input = rest;
+    Some(u64::from_le_bytes(*bytes))
+}
+
+fn decode_u32(input: &mut &[u8]) -> Option<u32> {
+    let (bytes, rest) = input.split_first_chunk::<4>()?;
+    *input = rest;
+    Some(u32::from_le_bytes(*bytes))
+}
+
+// FNV-1a. Guards against truncated or damaged files, not against tampering.
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+// Fingerprints the hasher by its output rather than its type name, which is not stable across
+// compiler versions. What matters is where keys land, so a hasher whose algorithm changed
+// between builds is caught as well.
+fn hasher_id<H: Default + Hasher>() -> u64 {
+    let mut hasher = H::default();
+    hasher.write(MAGIC);
+    hasher.finish()
+}
+
+fn probe_id<P: Probe>() -> u64 {
+    P::ID
+}
+
+fn native_flags() -> u32 {
+    if cfg!(target_endian = "big") { FLAG_BIG_ENDIAN } else { 0 }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Layout {
+    Packed,
+    Fixed { key_size: usize, value_size: usize },
+}
+
+impl Layout {
+    fn stride(&self) -> usize {
+        match self {
+            Self::Packed => 0,
+            Self::Fixed { key_size, value_size } => RECORD_HEADER_SIZE + key_size + value_size,
+        }
+    }
+}
+
+struct Header {
+    kind: u32,
+    layout: Layout,
+    flags: u32,
+    capacity: usize,
+    size: usize,
+    head: usize,
+    tail: usize,
+    hasher_id: u64,
+    probe_id: u64,
+    checksum: u64,
+}
+
+impl Header {
+    fn write(&self, out: &mut Vec<u8>, body_len: usize) {
+        let (layout, key_size, value_size) = match self.layout {
+            Layout::Packed => (LAYOUT_PACKED, 0, 0),
+            Layout::Fixed { key_size, value_size } => (LAYOUT_FIXED, key_size, value_size),
+        };
+        out.extend_from_slice(MAGIC);
+        for field in [VERSION, self.kind, layout, self.flags, key_size as u32, value_size as u32] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [self.capacity, self.size, self.head, self.tail] {
+            encode_index(out, field);
+        }
+        for field in [self.hasher_id, self.probe_id, body_len as u64, self.checksum] {
+            out.extend_from_slice(&field.to_le_bytes());
+        }
+    }
+
+    fn read(bytes: &[u8]) -> Result<(Header, &[u8]), SnapshotError> {
+        let Some((MAGIC, mut input)) = bytes.split_first_chunk::<8>() else {
+            return Err(SnapshotError::NotASnapshot);
+        };
+        if bytes.len() < HEADER_SIZE {
+            return Err(SnapshotError::Corrupt);
+        }
+
+        let mut next_u32 = || decode_u32(&mut input).ok_or(SnapshotError::Corrupt);
+        let version = next_u32()?;
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let (kind, layout, flags) = (next_u32()?, next_u32()?, next_u32()?);
+        let (key_size, value_size) = (next_u32()? as usize, next_u32()? as usize);
+        let layout = match layout {
+            LAYOUT_PACKED => Layout::Packed,
+            LAYOUT_FIXED => Layout::Fixed { key_size, value_size },
+            _ => return Err(SnapshotError::Corrupt),
+        };
+
+        let mut next_u64 = || decode_u64(&mut input).ok_or(SnapshotError::Corrupt);
+        let (capacity, size, head, tail) = (next_u64()?, next_u64()?, next_u64()?, next_u64()?);
+        let (hasher_id, probe_id) = (next_u64()?, next_u64()?);
+        let (body_len, checksum) = (next_u64()?, next_u64()?);
+
+        let to_usize = |n: u64| usize::try_from(n).map_err(|_| SnapshotError::Corrupt);
+        if input.len() as u64 != body_len {
+            return Err(SnapshotError::Corrupt);
+        }
+        let header = Header {
+            kind,
+            layout,
+            flags,
+            capacity: to_usize(capacity)?,
+            size: to_usize(size)?,
+            head: to_usize(head)?,
+            tail: to_usize(tail)?,
+            hasher_id,
+            probe_id,
+            checksum,
+        };
+        Ok((header, input))
+    }
+
+    fn check<H, P>(&self, kind: u32, body: &[u8]) -> Result<(), SnapshotError>
+    where
+        H: Default + Hasher,
+        P: Probe,
+    {
+        if self.kind != kind {
+            return Err(SnapshotError::WrongKind);
+        }
+        if self.flags != native_flags() {
+            return Err(SnapshotError::LayoutMismatch);
+        }
+        if self.hasher_id != hasher_id::<H>() {
+            return Err(SnapshotError::HasherMismatch);
+        }
+        if self.probe_id != probe_id::<P>() {
+            return Err(SnapshotError::ProbeMismatch);
+        }
+        if self.checksum != checksum(body) {
+            return Err(SnapshotError::ChecksumMismatch);
+        }
+        self.check_capacity(body)
+    }
+
+    // The capacity is not covered by the checksum, so it is bounded by the body before anything
+    // is allocated for it. Every packed record takes at least its state byte.
+    fn check_capacity(&self, body: &[u8]) -> Result<(), SnapshotError> {
+        let fits_body = match self.layout {
+            Layout::Packed => self.capacity <= body.len(),
+            Layout::Fixed { .. } => {
+                self.capacity.checked_mul(self.layout.stride()) == Some(body.len())
+            }
+        };
+        if !is_nonzero_and_within_limit(self.capacity, crate::MAX_CAPACITY) || !fits_body {
+            return Err(SnapshotError::Corrupt);
+        }
+        Ok(())
+    }
+}
+
+// Writes every slot of table. write_entry appends an occupied slot's key, value and extras;
+// with the fixed layout it must write exactly key_size + value_size bytes.
+pub(crate) fn write_table<K, V, H, E, P, F>(
+    table: &FixedSizeHashMapImpl<K, V, H, E, P>,
+    kind: u32,
+    layout: Layout,
+    mut write_entry: F,
+) -> Vec<u8>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    E: Entry<K, V>,
+    P: Probe,
+    F: FnMut(&E, &mut Vec<u8>),
+{
+    let capacity = table.capacity();
+    let mut body = Vec::with_capacity(capacity * layout.stride().max(1));
+    for i in 0..capacity {
+        match table.slots().get(i) {
+            Some(Slot::IsOccupiedBy(entry)) => {
+                body.push(STATE_OCCUPIED);
+                body.extend_from_slice(&entry.hash().to_le_bytes());
+                encode_index(&mut body, entry.next());
+                encode_index(&mut body, entry.prev());
+                write_entry(entry, &mut body);
+            }
+            slot => {
+                body.push(match slot {
+                    Some(Slot::WasOccupied) => STATE_TOMBSTONE,
+                    _ => STATE_EMPTY,
+                });
+                if let Layout::Fixed { .. } = layout {
+                    body.resize(body.len() + layout.stride() - 1, 0);
+                }
+            }
+        }
+    }
+
+    let header = Header {
+        kind,
+        layout,
+        flags: native_flags(),
+        capacity,
+        size: table.size(),
+        head: table.head_index(),
+        tail: table.tail_index(),
+        hasher_id: hasher_id::<H>(),
+        probe_id: probe_id::<P>(),
+        checksum: checksum(&body),
+    };
+    let mut out = Vec::with_capacity(HEADER_SIZE + body.len());
+    header.write(&mut out, body.len());
+    out.extend_from_slice(&body);
+    out
+}
+
+// Loads a table written by write_table. read_entry decodes an occupied slot's key, value and
+// extras into an entry with the given hash. expected_capacity rejects snapshots of a different
+// size before anything is decoded.
+pub(crate) fn read_table<K, V, H, E, P, F>(
+    bytes: &[u8],
+    kind: u32,
+    expected_capacity: Option<usize>,
+    mut read_entry: F,
+) -> Result<FixedSizeHashMapImpl<K, V, H, E, P>, SnapshotError>
+where
+    K: Hash + std::cmp::Eq,
+    H: Default + Hasher,
+    E: Entry<K, V>,
+    P: Probe,
+    F: FnMut(&mut &[u8], u64) -> Option<E>,
+{
+    let (header, body) = Header::read(bytes)?;
+    if let Some(expected) = expected_capacity
+        && expected != header.capacity
+    {
+        return Err(SnapshotError::CapacityMismatch {
+            expected,
+            found: header.capacity,
+        });
+    }
+    header.check::<H, P>(kind, body)?;
+
+    let stride = header.layout.stride();
+    let mut input = body;
+    let mut data = Vec::with_capacity(header.capacity);
+    for _ in 0..header.capacity {
+        let record_start = input.len();
+        let state = u8::decode(&mut input).ok_or(SnapshotError::Corrupt)?;
+        match state {
+            STATE_OCCUPIED => {
+                let hash = decode_u64(&mut input).ok_or(SnapshotError::Corrupt)?;
+                let next = decode_index(&mut input).ok_or(SnapshotError::Corrupt)?;
+                let prev = decode_index(&mut input).ok_or(SnapshotError::Corrupt)?;
+                let mut entry = read_entry(&mut input, hash).ok_or(SnapshotError::Corrupt)?;
+                if stride != 0 && record_start - input.len() != stride {
+                    return Err(SnapshotError::LayoutMismatch);
+                }
+                *entry.mut_next() = next;
+                *entry.mut_prev() = prev;
+                data.push(Slot::IsOccupiedBy(entry));
+            }
+            STATE_EMPTY | STATE_TOMBSTONE => {
+                if stride != 0 {
+                    input = input.get(stride - 1..).ok_or(SnapshotError::Corrupt)?;
+                }
+                data.push(if state == STATE_EMPTY { Slot::Empty } else { Slot::WasOccupied });
+            }
+            _ => return Err(SnapshotError::Corrupt),
+        }
+    }
+    if !input.is_empty() {
+        return Err(SnapshotError::Corrupt);
+    }
+
+    FixedSizeHashMapImpl::from_slots(data, header.head, header.tail)
+        .filter(|table| table.size() == header.size)
+        .ok_or(SnapshotError::Corrupt)
+}
+
+// Read-only map over a fixed layout snapshot, looked up in place. The bytes can come from a
+// file read into memory or from a memory map; nothing is decoded up front.
+pub struct SnapshotView<'a, K, V, H = DefaultHasher, P = LinearProbe> {
+    _body: &'a [u8],
+    _capacity: usize,
+    _size: usize,
+    _head: usize,
+    _phantom: PhantomData<(K, V, H, P)>,
+}
+
+impl<'a, K, V, H, P> SnapshotView<'a, K, V, H, P>
+where
+    K: Pod + Hash + std::cmp::Eq,
+    V: Pod,
+    H: Default + Hasher,
+    P: Probe,
+{
+    const STRIDE: usize = RECORD_HEADER_SIZE + mem::size_of::<K>() + mem::size_of::<V>();
+
+    // Checks the header and checksum. Does not walk the list; a view over a damaged snapshot
+    // that passes the checksum may miss entries but never reads outside the bytes.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, SnapshotError> {
+        let (header, body) = Header::read(bytes)?;
+        let layout = Layout::Fixed {
+            key_size: mem::size_of::<K>(),
+            value_size: mem::size_of::<V>(),
+        };
+        if header.kind != KIND_MAP {
+            return Err(SnapshotError::WrongKind);
+        }
+        if header.layout != layout {
+            return Err(SnapshotError::LayoutMismatch);
+        }
+        header.check::<H, P>(KIND_MAP, body)?;
+
+        Ok(Self {
+            _body: body,
+            _capacity: header.capacity,
+            _size: header.size,
+            _head: header.head,
+            _phantom: PhantomData,
+        })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._capacity
+    }
+
+    pub fn size(&self) -> usize {
+        self._size
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut hash_state = H::default();
+        key.hash(&mut hash_state);
+        let hash = hash_state.finish();
+
+        for step in 0..self._capacity {
+            let index = P::index(hash, step, self._capacity);
+            let record = read_record::<K, V>(self._body, index, Self::STRIDE)?;
+            match record.state {
+                STATE_OCCUPIED => {
+                    if record.hash == hash && record.key == *key {
+                        return Some(record.value);
+                    }
+                    let home = P::index(record.hash, 0, self._capacity);
+                    if P::ROBIN_HOOD && (index + self._capacity - home) % self._capacity < step {
+                        return None;
+                    }
+                }
+                STATE_TOMBSTONE => {}
+                _ => return None,
+            }
+        }
+        None
+    }
+
+    pub fn exists(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn iter(&self) -> SnapshotIter<'a, K, V> {
+        SnapshotIter {
+            _body: self._body,
+            _next: self._head,
+            _remaining: self._size,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+struct Record<K, V> {
+    state: u8,
+    hash: u64,
+    next: usize,
+    key: K,
+    value: V,
+}
+
+fn read_record<K: Pod, V: Pod>(body: &[u8], index: usize, stride: usize) -> Option<Record<K, V>> {
+    let mut input = body.get(index.checked_mul(stride)?..)?.get(..stride)?;
+    let state = u8::decode(&mut input)?;
+    let hash = decode_u64(&mut input)?;
+    let next = decode_index(&mut input)?;
+    let _prev = decode_index(&mut input)?;
+    Some(Record {
+        state,
+        hash,
+        next,
+        key: K::decode(&mut input)?,
+        value: V::decode(&mut input)?,
+    })
+}
+
+// Entries of a SnapshotView in list order, copied out of the snapshot.
+pub struct SnapshotIter<'a, K, V> {
+    _body: &'a [u8],
+    _next: usize,
+    _remaining: usize,
+    _phantom: PhantomData<(K, V)>,
+}
+
+impl<K: Pod, V: Pod> Iterator for SnapshotIter<'_, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self._remaining == 0 {
+            return None;
+        }
+        let stride = RECORD_HEADER_SIZE + mem::size_of::<K>() + mem::size_of::<V>();
+        let record = read_record::<K, V>(self._body, self._next, stride)
+            .filter(|record| record.state == STATE_OCCUPIED);
+        let Some(record) = record else {
+            self._remaining = 0;
+            return None;
+        };
+        self._remaining -= 1;
+        self._next = record.next;
+        Some((record.key, record.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self._remaining))
+    }
+}
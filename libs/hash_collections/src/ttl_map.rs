@@ -0,0 +1,347 @@
+#![allow(dead_code)]
+
+use std::borrow::Borrow;
+use std::hash::{DefaultHasher, Hash, Hasher};
+#[cfg(feature = "nightly")]
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "nightly")]
+use crate::check::{Check, IsTrue, is_nonzero_and_within_limit};
+use crate::{
+    check::prime_at_least,
+    errors::InsertError,
+    hash_map::{MapEntry, MapImpl},
+    hash_map_internal::{Entry, FixedSizeHashMapImpl, MapIteratorImpl, SlotLookup},
+    probe::{LinearProbe, Probe},
+};
+
+// Source of the current time for expiry checks. Tests inject a clock they can advance by hand.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+// Values are stored together with their deadline, None meaning the entry never expires.
+type TtlValue<V> = (V, Option<Instant>);
+
+fn _is_live<V>(value: &TtlValue<V>, now: Instant) -> bool {
+    value.1.is_none_or(|deadline| now < deadline)
+}
+
+// Expired entries are treated as absent straight away, but keep their slot until they are
+// overwritten, removed, purged, or an insert needs the room.
+pub struct DynTtlHashMap<K, V, T = SystemClock, H = DefaultHasher, P = LinearProbe>
+where
+    K: Hash + std::cmp::Eq,
+    T: Clock,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _hash_map_internal: MapImpl<K, TtlValue<V>, H, P>,
+    _clock: T,
+}
+
+impl<K, V, T, H, P> DynTtlHashMap<K, V, T, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    T: Clock + Default,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new(capacity: usize) -> DynTtlHashMap<K, V, T, H, P> {
+        Self::with_clock(capacity, T::default())
+    }
+}
+
+impl<K, V, T, H, P> DynTtlHashMap<K, V, T, H, P>
+where
+    K: Hash + std::cmp::Eq,
+    T: Clock,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn with_clock(capacity: usize, clock: T) -> DynTtlHashMap<K, V, T, H, P> {
+        DynTtlHashMap::<K, V, T, H, P> {
+            _hash_map_internal: FixedSizeHashMapImpl::new(prime_at_least(capacity)),
+            _clock: clock,
+        }
+    }
+
+    pub fn clock(&self) -> &T {
+        &self._clock
+    }
+
+    // Inserts an entry that never expires.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, InsertError<K, V>> {
+        self._insert(key, value, None)
+    }
+
+    pub fn insert_with_ttl(
+        &mut self,
+        key: K,
+        value: V,
+        ttl: Duration,
+    ) -> Result<Option<V>, InsertError<K, V>> {
+        let deadline = self._clock.now().checked_add(ttl);
+        self._insert(key, value, deadline)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let now = self._clock.now();
+        self._hash_map_internal
+            .get_entry_and_index_of(key)
+            .map(|(e, _)| e.value())
+            .filter(|value| _is_live(value, now))
+            .map(|value| &value.0)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let now = self._clock.now();
+        self._hash_map_internal
+            .get_mut_entry_and_index_of(key)
+            .map(|(e, _)| e.mut_value())
+            .filter(|value| _is_live(value, now))
+            .map(|value| &mut value.0)
+    }
+
+    pub fn exists<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    // Time left before the entry expires. None if it is absent or never expires.
+    pub fn ttl<Q>(&self, key: &Q) -> Option<Duration>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let now = self._clock.now();
+        self._hash_map_internal
+            .get_entry_and_index_of(key)
+            .and_then(|(e, _)| e.value().1)
+            .and_then(|deadline| deadline.checked_duration_since(now))
+            .filter(|left| !left.is_zero())
+    }
+
+    // Removes the entry, only returning its value if it had not expired yet.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + std::cmp::Eq + ?Sized,
+    {
+        let now = self._clock.now();
+        self._hash_map_internal
+            .remove(key)
+            .map(|e| e.consume_self())
+            .filter(|value| _is_live(value, now))
+            .map(|value| value.0)
+    }
+
+    // Walks the list from its head and drops every expired entry. Returns how many were dropped.
+    pub fn purge_expired(&mut self) -> usize {
+        let now = self._clock.now();
+        let mut purged = 0;
+        let mut i = self._hash_map_internal.head_index();
+        while let Some(entry) = self._hash_map_internal.get_entry_at(i) {
+            let next = entry.next();
+            if !_is_live(entry.value(), now) {
+                self._hash_map_internal.take_at(i);
+                purged += 1;
+            }
+            i = next;
+        }
+
+        if self._hash_map_internal.needs_compaction() {
+            self._hash_map_internal.compact();
+        }
+        purged
+    }
+
+    pub fn compact(&mut self) {
+        self._hash_map_internal.compact()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self._hash_map_internal.capacity()
+    }
+
+    // Counts live entries only, like iter_head. Expired entries that still hold a slot are
+    // skipped, which makes this a walk over the list rather than a stored count.
+    pub fn size(&self) -> usize {
+        self.iter_head().count()
+    }
+
+    // Live entries in insertion order.
+    pub fn iter_head(&self) -> TtlIter<'_, K, V> {
+        TtlIter {
+            _inner_iter: self._hash_map_internal.iter_head(),
+            _now: self._clock.now(),
+        }
+    }
+
+    fn _insert(
+        &mut self,
+        key: K,
+        value: V,
+        deadline: Option<Instant>,
+    ) -> Result<Option<V>, InsertError<K, V>> {
+        let now = self._clock.now();
+        let hash = self._hash_map_internal.hash_of(&key);
+        let mut slot = self._hash_map_internal.find_slot(hash, &key);
+        if matches!(slot, SlotLookup::Vacant(i) if i == self.capacity()) && self.purge_expired() > 0 {
+            slot = self._hash_map_internal.find_slot(hash, &key);
+        }
+
+        match slot {
+            SlotLookup::Occupied(i) => {
+                let old_value = self
+                    ._hash_map_internal
+                    .get_mut_entry_at(i)
+                    .map(|e| std::mem::replace(e.mut_value(), (value, deadline)))
+                    .filter(|old_value| _is_live(old_value, now))
+                    .map(|old_value| old_value.0);
+                self._hash_map_internal.move_to_back(i);
+                Ok(old_value)
+            }
+            SlotLookup::Vacant(i) => self
+                ._hash_map_internal
+                .insert_at(i, hash, key, (value, deadline))
+                .map(|_| None)
+                .map_err(|error| InsertError {
+                    capacity: error.capacity,
+                    key: error.key,
+                    value: error.value.0,
+                }),
+        }
+    }
+}
+
+pub struct TtlIter<'a, K: 'a, V: 'a> {
+    _inner_iter: MapIteratorImpl<'a, K, TtlValue<V>, MapEntry<K, TtlValue<V>>>,
+    _now: Instant,
+}
+
+impl<'a, K: 'a, V: 'a> Iterator for TtlIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let now = self._now;
+        self._inner_iter
+            .find(|e| _is_live(e.value(), now))
+            .map(|e| (e.key(), &e.value().0))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self._inner_iter.size_hint().1)
+    }
+}
+
+#[cfg(feature = "nightly")]
+pub struct TtlHashMap<K, V, const C: usize, T = SystemClock, H = DefaultHasher, P = LinearProbe>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    T: Clock,
+    H: Default + Hasher,
+    P: Probe,
+{
+    _hash_map: DynTtlHashMap<K, V, T, H, P>,
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, T, H, P> Default for TtlHashMap<K, V, C, T, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    T: Clock + Default,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn default() -> Self {
+        Self::with_clock(T::default())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, T, H, P> TtlHashMap<K, V, C, T, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    T: Clock + Default,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn new() -> TtlHashMap<K, V, C, T, H, P> {
+        Self::with_clock(T::default())
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, T, H, P> TtlHashMap<K, V, C, T, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    T: Clock,
+    H: Default + Hasher,
+    P: Probe,
+{
+    pub fn with_clock(clock: T) -> TtlHashMap<K, V, C, T, H, P> {
+        TtlHashMap::<K, V, C, T, H, P> {
+            _hash_map: DynTtlHashMap::with_clock(C, clock),
+        }
+    }
+
+    // The slot table rounds C up to a prime, like DynTtlHashMap::new does.
+    pub const fn capacity(&self) -> usize {
+        prime_at_least(C)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, T, H, P> Deref for TtlHashMap<K, V, C, T, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    T: Clock,
+    H: Default + Hasher,
+    P: Probe,
+{
+    type Target = DynTtlHashMap<K, V, T, H, P>;
+    fn deref(&self) -> &Self::Target {
+        &self._hash_map
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<K, V, const C: usize, T, H, P> DerefMut for TtlHashMap<K, V, C, T, H, P>
+where
+    Check<{ is_nonzero_and_within_limit(C, crate::MAX_CAPACITY) }>: IsTrue,
+    K: Hash + std::cmp::Eq,
+    T: Clock,
+    H: Default + Hasher,
+    P: Probe,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self._hash_map
+    }
+}
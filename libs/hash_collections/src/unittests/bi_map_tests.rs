@@ -0,0 +1,94 @@
+#![cfg(test)]
+
+use crate::{BiMapInsertError, FixedSizeBiMap, probe::RobinHoodProbe};
+
+type MyBiMap = FixedSizeBiMap<String, u64, 7>;
+
+fn add_some_data(bi_map: &mut MyBiMap) {
+    let pairs = [("foo", 100), ("bar", 200), ("baz", 300)];
+    for (left, right) in pairs {
+        assert_eq!(bi_map.insert(left.to_string(), right), Ok(true));
+    }
+}
+
+#[test]
+fn lookups_on_both_sides() {
+    let mut bi_map = MyBiMap::new();
+    add_some_data(&mut bi_map);
+
+    assert_eq!(bi_map.get_by_left("bar"), Some(&200));
+    assert_eq!(bi_map.get_by_right(&300), Some(&String::from("baz")));
+    assert_eq!(bi_map.get_by_left("zoo"), None);
+    assert_eq!(bi_map.get_by_right(&400), None);
+    assert!(bi_map.contains_left("foo") && bi_map.contains_right(&100));
+    assert_eq!(bi_map.size(), 3);
+    assert_eq!(
+        bi_map.iter_head().map(|(l, r)| (l.as_str(), *r)).collect::<Vec<_>>(),
+        vec![("foo", 100), ("bar", 200), ("baz", 300)]
+    );
+    assert_eq!(format!("{:?}", bi_map), r#"{"foo": 100, "bar": 200, "baz": 300}"#);
+}
+
+#[test]
+fn insert_reports_conflicts() {
+    let mut bi_map = MyBiMap::new();
+    add_some_data(&mut bi_map);
+
+    assert_eq!(bi_map.insert(String::from("foo"), 100), Ok(false));
+    assert_eq!(
+        bi_map.insert(String::from("foo"), 400),
+        Err(BiMapInsertError::LeftConflict(String::from("foo"), 400))
+    );
+    assert_eq!(
+        bi_map.insert(String::from("bat"), 200),
+        Err(BiMapInsertError::RightConflict(String::from("bat"), 200))
+    );
+    let err = bi_map.insert(String::from("foo"), 200).unwrap_err();
+    assert_eq!(err, BiMapInsertError::BothConflict(String::from("foo"), 200));
+    assert_eq!(err.into_inner(), (String::from("foo"), 200));
+
+    assert_eq!(bi_map.get_by_left("foo"), Some(&100));
+    assert_eq!(bi_map.get_by_right(&200), Some(&String::from("bar")));
+    assert_eq!(bi_map.size(), 3);
+
+    let full = FixedSizeBiMap::<u64, u64, 3>::try_from_iter((0..4).map(|i| (i, i * 10)));
+    assert!(matches!(full, Err(BiMapInsertError::OutOfCapacity(_))));
+}
+
+#[test]
+fn remove_keeps_tables_in_sync() {
+    let mut bi_map = MyBiMap::new();
+    add_some_data(&mut bi_map);
+
+    assert_eq!(bi_map.remove_by_left("foo"), Some((String::from("foo"), 100)));
+    assert_eq!(bi_map.get_by_right(&100), None);
+    assert_eq!(bi_map.remove_by_right(&300), Some((String::from("baz"), 300)));
+    assert!(!bi_map.contains_left("baz"));
+    assert_eq!(bi_map.remove_by_left("foo"), None);
+    assert_eq!(bi_map.size(), 1);
+
+    assert_eq!(bi_map.insert(String::from("foo"), 300), Ok(true));
+    let cloned = bi_map.clone();
+    assert_eq!(cloned, bi_map);
+    bi_map.clear();
+    assert_eq!(bi_map.size(), 0);
+    assert_eq!(bi_map.get_by_right(&300), None);
+    assert_eq!(cloned.get_by_right(&300), Some(&String::from("foo")));
+}
+
+#[test]
+fn robin_hood_tables_stay_in_sync() {
+    let mut bi_map =
+        FixedSizeBiMap::<u64, String, 11, std::hash::DefaultHasher, RobinHoodProbe>::try_from_iter(
+            (0..10).map(|i| (i, i.to_string())),
+        )
+        .unwrap();
+    for i in (0..10).step_by(3) {
+        assert_eq!(bi_map.remove_by_left(&i), Some((i, i.to_string())));
+    }
+    for i in 0..10 {
+        let expected = (i % 3 != 0).then(|| i.to_string());
+        assert_eq!(bi_map.get_by_left(&i), expected.as_ref());
+        assert_eq!(bi_map.get_by_right(&i.to_string()).copied(), expected.map(|_| i));
+    }
+}
@@ -0,0 +1,91 @@
+#![cfg(test)]
+
+use crate::FixedSizeCounter;
+
+type MyCounter = FixedSizeCounter<String, 13>;
+
+fn count_words(text: &str) -> MyCounter {
+    MyCounter::try_from_iter(text.split(' ').map(|word| word.to_string())).unwrap()
+}
+
+#[test]
+fn add_subtract_and_count() {
+    let mut counter = MyCounter::new();
+    assert_eq!(counter.add(String::from("foo"), 2), Ok(2));
+    assert_eq!(counter.add(String::from("bar"), 1), Ok(1));
+    assert_eq!(counter.add(String::from("foo"), 3), Ok(5));
+    assert_eq!(counter.add(String::from("baz"), 0), Ok(0));
+    assert!(!counter.exists("baz"));
+
+    assert_eq!(counter.count("foo"), 5);
+    assert_eq!(counter.count("zoo"), 0);
+    assert_eq!(counter.total(), 6);
+    assert_eq!(counter.size(), 2);
+
+    assert_eq!(counter.subtract("foo", 4), 1);
+    assert_eq!(counter.subtract("bar", 7), 0);
+    assert_eq!(counter.subtract("zoo", 1), 0);
+    assert!(!counter.exists("bar"));
+    assert_eq!(counter.total(), 1);
+
+    assert_eq!(counter.remove("foo"), 1);
+    assert_eq!(counter.total(), 0);
+    assert_eq!(counter.size(), 0);
+}
+
+#[test]
+fn most_common_breaks_ties_by_first_count() {
+    let counter = count_words("the cat and the dog and the bird");
+    assert_eq!(format!("{:?}", counter), r#"{"the": 3, "cat": 1, "and": 2, "dog": 1, "bird": 1}"#);
+
+    let most_common = counter.most_common(3);
+    assert_eq!(
+        most_common.iter().map(|(word, count)| (word.as_str(), *count)).collect::<Vec<_>>(),
+        vec![("the", 3), ("and", 2), ("cat", 1)]
+    );
+    assert_eq!(counter.most_common(10).len(), 5);
+    assert_eq!(counter.total(), 8);
+}
+
+#[test]
+fn counter_arithmetic() {
+    let left = count_words("a a a b b c");
+    let right = count_words("a b b b d");
+
+    let sum = left.sum(&right).unwrap();
+    assert_eq!(sum.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("a", 4), ("b", 5), ("c", 1), ("d", 1)]);
+    assert_eq!(sum.total(), 11);
+
+    let difference = (&left - &right).unwrap();
+    assert_eq!(difference.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("a", 2), ("c", 1)]);
+    let union = (&left | &right).unwrap();
+    assert_eq!(union.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("a", 3), ("b", 3), ("c", 1), ("d", 1)]);
+    let intersection = (&left & &right).unwrap();
+    assert_eq!(intersection.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("a", 1), ("b", 2)]);
+    assert_eq!(intersection.capacity(), 13);
+
+    let small = FixedSizeCounter::<u64, 2>::try_from_iter([1, 2, 2]).unwrap();
+    let other = FixedSizeCounter::<u64, 3>::try_from_iter([3]).unwrap();
+    assert_eq!((&small | &other).unwrap_err().capacity, 2);
+    let err = FixedSizeCounter::<u64, 2>::try_from_iter([1, 2, 3]).unwrap_err();
+    assert_eq!(err.into_inner(), (3, 1));
+}
+
+#[test]
+fn clone_compare_and_clear() {
+    let mut counter = count_words("foo bar foo");
+    let mut cloned = counter.clone();
+    assert_eq!(cloned, counter);
+    assert_eq!(cloned.subtract("bar", 1), 0);
+    assert_ne!(cloned, counter);
+    cloned.extend([String::from("bar")]);
+    assert_eq!(cloned, counter);
+
+    counter.clear();
+    assert_eq!(counter.total(), 0);
+    assert_eq!(counter.most_common(1), vec![]);
+}
@@ -0,0 +1,143 @@
+#![cfg(test)]
+
+use crate::{
+    BiMapInsertError, DynFixedBiMap, DynFixedCounter, DynFixedHashGraphMap, DynFixedHashMap, DynFixedHashSet, DynFixedMultiMap,
+    InsertError,
+};
+
+#[test]
+fn capacity_rounds_up_to_prime() {
+    assert_eq!(DynFixedHashMap::<String, u64>::new(10).capacity(), 11);
+    assert_eq!(DynFixedHashMap::<String, u64>::new(13).capacity(), 13);
+    assert_eq!(DynFixedHashMap::<String, u64>::new(0).capacity(), 2);
+    assert_eq!(DynFixedHashSet::<String>::new(100).capacity(), 101);
+    assert_eq!(DynFixedHashGraphMap::<String, u64>::new(90).capacity(), 97);
+}
+
+#[test]
+fn map_insert_get_and_remove() {
+    let mut map = DynFixedHashMap::<String, u64>::new(4);
+    assert_eq!(map.capacity(), 5);
+
+    for (i, key) in ["foo", "bar", "baz", "bat", "boo"].iter().enumerate() {
+        assert_eq!(map.insert(String::from(*key), i as u64), Ok(None));
+    }
+    assert_eq!(map.size(), 5);
+    assert_eq!(
+        map.insert(String::from("fat"), 5),
+        Err(InsertError { capacity: 5, key: String::from("fat"), value: 5 })
+    );
+
+    assert_eq!(map.get("baz"), Some(&2));
+    map["baz"] += 10;
+    assert_eq!(map["baz"], 12);
+    assert_eq!(map.remove("foo"), Some(0));
+    assert_eq!(map.head(), Some((&String::from("bar"), &1)));
+
+    *map.entry(String::from("fat")).or_insert(0).unwrap() += 7;
+    assert_eq!(map.tail(), Some((&String::from("fat"), &7)));
+    assert_eq!(
+        map.iter_head().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+        vec!["bar", "baz", "bat", "boo", "fat"]
+    );
+}
+
+#[test]
+fn set_insert_and_remove() {
+    let mut set = DynFixedHashSet::<String>::new(3);
+    assert_eq!(set.insert(String::from("foo")), Ok(true));
+    assert_eq!(set.insert(String::from("foo")), Ok(false));
+    assert_eq!(set.insert(String::from("bar")), Ok(true));
+    assert_eq!(set.insert(String::from("baz")), Ok(true));
+    assert_eq!(
+        set.insert(String::from("bat")),
+        Err(InsertError { capacity: 3, key: String::from("bat"), value: () })
+    );
+    assert!(set.remove("bar"));
+    assert!(!set.exists("bar"));
+    assert_eq!(set.iter_head().collect::<Vec<_>>(), vec!["foo", "baz"]);
+}
+
+#[test]
+fn counter_add_and_subtract() {
+    let mut counter = DynFixedCounter::<String>::new(2);
+    assert_eq!(counter.add(String::from("foo"), 2), Ok(2));
+    assert_eq!(counter.add(String::from("bar"), 1), Ok(1));
+    assert_eq!(
+        counter.add(String::from("baz"), 4),
+        Err(InsertError { capacity: 2, key: String::from("baz"), value: 4 })
+    );
+    assert_eq!(counter.subtract("foo", 1), 1);
+    assert_eq!(counter.total(), 2);
+    assert_eq!((&counter - &counter).map(|c| c.size()), Ok(0));
+}
+
+#[test]
+fn multi_map_shares_value_pool() {
+    let mut multi_map = DynFixedMultiMap::<String, u64>::new(2);
+    assert_eq!(multi_map.capacity(), 2);
+    assert_eq!(multi_map.insert(String::from("foo"), 1), Ok(()));
+    assert_eq!(multi_map.insert(String::from("foo"), 2), Ok(()));
+    assert_eq!(
+        multi_map.insert(String::from("bar"), 3),
+        Err(InsertError { capacity: 2, key: String::from("bar"), value: 3 })
+    );
+    assert_eq!(multi_map.remove_one("foo", &1), Some(1));
+    assert_eq!(multi_map.insert(String::from("bar"), 3), Ok(()));
+    assert_eq!(multi_map.get_all("foo").collect::<Vec<_>>(), vec![&2]);
+}
+
+#[test]
+fn bi_map_insert_and_remove() {
+    let mut bi_map = DynFixedBiMap::<String, u64>::new(2);
+    assert_eq!(bi_map.capacity(), 2);
+    assert_eq!(bi_map.insert(String::from("foo"), 1), Ok(true));
+    assert_eq!(bi_map.insert(String::from("bar"), 2), Ok(true));
+    assert_eq!(
+        bi_map.insert(String::from("baz"), 3),
+        Err(BiMapInsertError::OutOfCapacity(InsertError {
+            capacity: 2,
+            key: String::from("baz"),
+            value: 3
+        }))
+    );
+    assert_eq!(bi_map.remove_by_right(&1), Some((String::from("foo"), 1)));
+    assert_eq!(bi_map.insert(String::from("baz"), 3), Ok(true));
+    assert_eq!(bi_map.get_by_left("baz"), Some(&3));
+}
+
+#[test]
+fn graph_insert_and_remove() {
+    let mut graph = DynFixedHashGraphMap::<String, u64>::new(20);
+    let _ = graph.insert(
+        (String::from("foo"), 1),
+        vec![(String::from("bar"), 2), (String::from("baz"), 3)],
+    );
+    graph.connect_to("bar", vec!["baz", "baz"]);
+
+    assert_eq!(graph.size(), 3);
+    assert_eq!(graph.out_edge_weight("foo", "bar"), 1);
+    assert_eq!(graph.out_edge_weight("bar", "baz"), 2);
+
+    graph.remove("baz");
+    graph.compact();
+    assert_eq!(graph.out_edge_weight("bar", "baz"), 0);
+    assert_eq!(
+        graph.iter_out_edges("foo").map(|(n, w)| (n.key().as_str(), w)).collect::<Vec<_>>(),
+        vec![("bar", 1)]
+    );
+}
+
+#[test]
+fn grow_keeps_order_and_never_shrinks() {
+    let mut map = DynFixedHashMap::<u64, u64>::new(3);
+    for i in 0..3 {
+        let _ = map.insert(i, i);
+    }
+    map.grow(8);
+    assert_eq!(map.capacity(), 11);
+    let _ = map.insert(3, 3);
+    map.grow(4);
+    assert_eq!(map.capacity(), 11);
+    assert_eq!(map.iter_head().map(|(k, _)| *k).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+}
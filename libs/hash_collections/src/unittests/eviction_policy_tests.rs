@@ -0,0 +1,180 @@
+#![cfg(test)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{ClockCache, EvictionPolicy, LfuCache, LruCache};
+
+fn fill_and_touch<E: EvictionPolicy<u64, u64>>(cache: &mut E) -> Vec<u64> {
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let sink = evicted.clone();
+    cache.set_eviction_callback(move |k, _| sink.borrow_mut().push(k));
+
+    for i in 0..3 {
+        cache.insert(i, i * 10);
+    }
+    // 0 is used twice, 2 once, 1 never.
+    cache.get(&0);
+    cache.get(&0);
+    cache.get(&2);
+    cache.insert(3, 30);
+    cache.insert(4, 40);
+
+    evicted.take()
+}
+
+#[test]
+fn policies_are_interchangeable() {
+    assert_eq!(fill_and_touch(&mut LruCache::<u64, u64, 3>::new()), vec![1, 0]);
+    assert_eq!(fill_and_touch(&mut LfuCache::<u64, u64, 3>::new()), vec![1, 3]);
+    // CLOCK's victims depend on where the hand meets the keys in the slot array.
+    let mut clock_cache = ClockCache::<u64, u64, 3>::new();
+    assert_eq!(fill_and_touch(&mut clock_cache).len(), 2);
+    assert!(clock_cache.exists(&4));
+}
+
+fn fill_past_capacity<E: EvictionPolicy<u64, u64>>(cache: &mut E) {
+    for i in 0..=cache.capacity() as u64 {
+        cache.insert(i, i);
+    }
+    assert_eq!(cache.size(), cache.capacity());
+    assert!(cache.exists(&(cache.capacity() as u64)));
+}
+
+#[test]
+fn capacities_need_not_be_prime() {
+    let mut lru_cache = LruCache::<u64, u64, 100>::new();
+    assert_eq!(lru_cache.capacity(), 100);
+    fill_past_capacity(&mut lru_cache);
+    assert!(!lru_cache.exists(&0));
+
+    fill_past_capacity(&mut LfuCache::<u64, u64, 100>::new());
+    fill_past_capacity(&mut ClockCache::<u64, u64, 100>::new());
+}
+
+#[test]
+fn lfu_evicts_least_frequently_used() {
+    let mut cache = LfuCache::<String, u64, 3>::new();
+    let _ = cache.insert("foo".to_string(), 1);
+    let _ = cache.insert("bar".to_string(), 2);
+    let _ = cache.insert("baz".to_string(), 3);
+
+    assert_eq!(cache.get("foo"), Some(&1));
+    assert_eq!(cache.get("foo"), Some(&1));
+    assert_eq!(cache.get("baz"), Some(&3));
+    assert_eq!(cache.peek("bar"), Some(&2));
+    assert_eq!(cache.frequency("foo"), 3);
+    assert_eq!(cache.frequency("bar"), 1);
+
+    let _ = cache.insert("bat".to_string(), 4);
+    assert!(!cache.exists("bar"));
+    assert_eq!(cache.frequency("bat"), 1);
+
+    // bat is the only entry left with a single use.
+    assert_eq!(cache.evict(), Some(("bat".to_string(), 4)));
+    assert_eq!(cache.evict(), Some(("baz".to_string(), 3)));
+    assert_eq!(cache.evict(), Some(("foo".to_string(), 1)));
+    assert_eq!(cache.evict(), None);
+}
+
+#[test]
+fn lfu_breaks_ties_by_recency() {
+    let mut cache = LfuCache::<u64, u64, 5>::new();
+    for i in 0..5 {
+        let _ = cache.insert(i, i);
+    }
+    for i in [3, 1, 4] {
+        let _ = cache.get(&i);
+    }
+    if let Some(v) = cache.get_mut(&1) {
+        *v = 100;
+    }
+
+    let order: Vec<u64> = std::iter::from_fn(|| cache.evict().map(|(k, _)| k)).collect();
+    assert_eq!(order, vec![0, 2, 3, 4, 1]);
+}
+
+// Mirrors the cache with a plain vector of (key, uses, last use) and compares the victims.
+#[test]
+fn lfu_matches_reference_model() {
+    let mut cache = LfuCache::<u64, u64, 13>::new();
+    let mut model: Vec<(u64, u32, u64)> = Vec::new();
+    let mut seed: u64 = 42;
+
+    for tick in 0..5_000u64 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let key = (seed >> 33) % 29;
+
+        match (seed >> 20) % 4 {
+            0 => {
+                assert_eq!(cache.remove(&key).is_some(), model.iter().any(|e| e.0 == key));
+                model.retain(|e| e.0 != key);
+            }
+            1 => {
+                assert_eq!(cache.get(&key).is_some(), model.iter().any(|e| e.0 == key));
+                if let Some(e) = model.iter_mut().find(|e| e.0 == key) {
+                    e.1 += 1;
+                    e.2 = tick;
+                }
+            }
+            _ => {
+                if let Some(e) = model.iter_mut().find(|e| e.0 == key) {
+                    e.1 += 1;
+                    e.2 = tick;
+                } else {
+                    if model.len() == 13 {
+                        let victim = *model.iter().min_by_key(|e| (e.1, e.2)).unwrap();
+                        model.retain(|e| e.0 != victim.0);
+                        assert!(cache.peek(&victim.0).is_some());
+                    }
+                    model.push((key, 1, tick));
+                }
+                let _ = cache.insert(key, tick);
+            }
+        }
+
+        assert_eq!(cache.size(), model.len());
+        for e in model.iter() {
+            assert_eq!(cache.frequency(&e.0), e.1);
+        }
+    }
+}
+
+#[test]
+fn clock_gives_referenced_entries_a_second_chance() {
+    let mut cache = ClockCache::<String, u64, 3>::new();
+    let _ = cache.insert("foo".to_string(), 1);
+    let _ = cache.insert("bar".to_string(), 2);
+    let _ = cache.insert("baz".to_string(), 3);
+
+    // The first sweep clears every reference bit and evicts the first entry it reaches again.
+    let first = cache.evict().map(|(k, _)| k).unwrap();
+    assert_eq!(cache.size(), 2);
+
+    let survivors: Vec<String> = ["foo", "bar", "baz"]
+        .iter()
+        .map(|k| k.to_string())
+        .filter(|k| *k != first)
+        .collect();
+    assert!(cache.get(survivors[1].as_str()).is_some());
+
+    let _ = cache.insert("bat".to_string(), 4);
+    // bat and the entry read after the sweep are referenced, the other survivor is not.
+    assert_eq!(cache.evict().map(|(k, _)| k), Some(survivors[0].clone()));
+    assert!(cache.exists(survivors[1].as_str()));
+    assert!(cache.exists("bat"));
+}
+
+#[test]
+fn clock_insert_evicts_when_full() {
+    let mut cache = ClockCache::<u64, u64, 7>::new();
+    for i in 0..100 {
+        let _ = cache.insert(i, i);
+        let _ = cache.get(&(i / 2));
+        assert!(cache.size() <= 7);
+    }
+    assert_eq!(cache.size(), 7);
+    assert_eq!(cache.peek(&99), Some(&99));
+    assert_eq!(cache.remove(&99), Some(99));
+    assert_eq!(cache.size(), 6);
+}
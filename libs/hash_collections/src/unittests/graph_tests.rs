@@ -1,11 +1,24 @@
 #![cfg(test)]
 
+use std::hash::Hasher;
 use std::vec;
 
 use crate::FixedSizeHashGraphMap;
+use crate::hash_graph::FixedSizeHashGraphImpl;
 
 type MyGraph = FixedSizeHashGraphMap<String, (), 97>;
 
+#[derive(Default)]
+struct HighCollisionHasher {}
+
+impl Hasher for HighCollisionHasher {
+    fn finish(&self) -> u64 {
+        3
+    }
+
+    fn write(&mut self, _: &[u8]) {}
+}
+
 #[test]
 fn insert_edges_once() {
     let mut graph = MyGraph::new();
@@ -96,3 +109,175 @@ fn multiple_nodes_and_edges() {
     assert_eq!(graph.out_edge_weight(&"bat".to_string(), &"bar".to_string()), 0);
     assert_eq!(graph.out_edge_weight(&"bat".to_string(), &"baz".to_string()), 0);
 }
+
+#[test]
+fn borrowed_key_lookups() {
+    let mut graph = MyGraph::new();
+    let _ = graph.insert(
+        ("foo".to_string(), ()),
+        vec![("bar".to_string(), ()), ("baz".to_string(), ())],
+    );
+    graph.connect_to("bar", vec!["baz", "foo"]);
+
+    assert_eq!(graph.out_edge_weight("foo", "bar"), 1);
+    assert_eq!(graph.out_edge_weight("bar", "baz"), 1);
+    assert_eq!(graph.node("bar").map_or(0, |node| node.out_edge_weight("foo")), 1);
+
+    graph.disconnect_from("foo", vec!["bar"]);
+    assert_eq!(graph.out_edge_weight("foo", "bar"), 0);
+    assert_eq!(graph.iter_out_edges("foo").count(), 1);
+
+    graph.disconnect_all("bar");
+    assert_eq!(graph.iter_out_edges("bar").count(), 0);
+
+    graph.remove("baz");
+    assert!(graph.node("baz").is_none());
+    assert_eq!(graph.out_edge_weight("foo", "baz"), 0);
+}
+
+#[test]
+fn compaction_keeps_edges() {
+    let mut graph = FixedSizeHashGraphImpl::<String, u64, 97, HighCollisionHasher>::new();
+    let _ = graph.insert(
+        ("foo".to_string(), 1),
+        vec![("bar".to_string(), 2), ("baz".to_string(), 3)],
+    );
+    let _ = graph.insert(("bat".to_string(), 4), vec![("foo".to_string(), 1)]);
+    let _ = graph.insert(("boo".to_string(), 5), vec![("foo".to_string(), 1)]);
+    graph.connect_to("baz", vec!["bar", "bar"]);
+
+    graph.remove("bat");
+    assert_eq!(graph.tombstone_count(), 1);
+    graph.compact();
+    assert_eq!(graph.tombstone_count(), 0);
+
+    assert_eq!(graph.out_edge_weight("foo", "bar"), 1);
+    assert_eq!(graph.out_edge_weight("foo", "baz"), 1);
+    assert_eq!(graph.out_edge_weight("boo", "foo"), 1);
+    assert_eq!(graph.out_edge_weight("baz", "bar"), 2);
+    assert_eq!(graph.node("bat").map(|n| *n.value()), None);
+
+    graph.remove("foo");
+    assert_eq!(graph.iter_out_edges("boo").count(), 0);
+    assert_eq!(graph.out_edge_weight("baz", "bar"), 2);
+}
+
+#[test]
+fn grow_into_remaps_edges() {
+    let mut graph = FixedSizeHashGraphMap::<String, u64, 5>::new();
+    let _ = graph.insert(
+        ("foo".to_string(), 1),
+        vec![("bar".to_string(), 2), ("baz".to_string(), 3)],
+    );
+    let _ = graph.insert(("bat".to_string(), 4), vec![("foo".to_string(), 1)]);
+    graph.connect_to("baz", vec!["bar", "bar"]);
+    assert!(graph.insert(("boo".to_string(), 5), vec![("fat".to_string(), 6)]).is_err());
+
+    let mut graph = graph.grow_into::<31>();
+    assert_eq!(graph.capacity(), 31);
+    assert!(graph.insert(("fat".to_string(), 6), vec![("foo".to_string(), 1)]).is_ok());
+
+    assert_eq!(graph.out_edge_weight("foo", "bar"), 1);
+    assert_eq!(graph.out_edge_weight("foo", "baz"), 1);
+    assert_eq!(graph.out_edge_weight("bat", "foo"), 1);
+    assert_eq!(graph.out_edge_weight("baz", "bar"), 2);
+    assert_eq!(graph.out_edge_weight("fat", "foo"), 1);
+    assert_eq!(
+        graph.iter_out_edges("foo").map(|(n, _)| n.key().clone()).collect::<Vec<_>>(),
+        vec!["bar".to_string(), "baz".to_string()]
+    );
+
+    graph.remove("foo");
+    assert_eq!(graph.iter_out_edges("bat").count(), 0);
+}
+
+#[test]
+fn insert_reports_failed_connection() {
+    let mut graph = FixedSizeHashGraphMap::<String, u64, 3>::new();
+    let err = graph
+        .insert(
+            ("foo".to_string(), 1),
+            vec![
+                ("bar".to_string(), 2),
+                ("baz".to_string(), 3),
+                ("bat".to_string(), 4),
+                ("boo".to_string(), 5),
+            ],
+        )
+        .unwrap_err();
+
+    assert_eq!(err.connection, Some(2));
+    assert_eq!(err.error.into_inner(), ("bat".to_string(), 4));
+    assert_eq!(err.remaining, vec![("boo".to_string(), 5)]);
+    assert_eq!(graph.out_edge_weight("foo", "baz"), 1);
+
+    let err = graph
+        .insert(("fat".to_string(), 6), vec![("foo".to_string(), 1)])
+        .unwrap_err();
+    assert_eq!(err.connection, None);
+    assert_eq!(err.error.into_inner(), ("fat".to_string(), 6));
+    assert_eq!(err.remaining, vec![("foo".to_string(), 1)]);
+}
+
+#[test]
+fn retain_extract_if_and_clear() {
+    let mut graph = FixedSizeHashGraphMap::<String, u64, 31>::new();
+    let _ = graph.insert(
+        ("foo".to_string(), 1),
+        vec![("bar".to_string(), 2), ("baz".to_string(), 3)],
+    );
+    let _ = graph.insert(("bat".to_string(), 4), vec![("foo".to_string(), 1)]);
+    let _ = graph.insert(("boo".to_string(), 5), vec![("bar".to_string(), 2)]);
+
+    graph.retain(|key, _| key != "bar");
+    assert_eq!(graph.size(), 4);
+    assert!(graph.node("bar").is_none());
+    assert_eq!(graph.iter_out_edges("foo").count(), 1);
+    assert_eq!(graph.iter_out_edges("boo").count(), 0);
+    assert_eq!(graph.out_edge_weight("bat", "foo"), 1);
+
+    let extracted = graph.extract_if(|_, value| *value == 1).collect::<Vec<_>>();
+    assert_eq!(extracted, vec![("foo".to_string(), 1)]);
+    assert_eq!(graph.iter_out_edges("bat").count(), 0);
+    assert_eq!(graph.size(), 3);
+
+    graph.clear();
+    assert_eq!(graph.size(), 0);
+    assert_eq!(graph.tombstone_count(), 0);
+    assert!(graph.node("baz").is_none());
+}
+
+#[test]
+fn clone_debug_compare_and_collect() {
+    let graph = FixedSizeHashGraphMap::<String, u64, 31>::try_from_iter(vec![
+        (("foo".to_string(), 1), vec![("bar".to_string(), 2), ("bar".to_string(), 2)]),
+        (("baz".to_string(), 3), vec![("foo".to_string(), 1)]),
+    ])
+    .unwrap();
+    // Connecting to an existing node re-inserts it, which moves it to the back.
+    assert_eq!(
+        format!("{:?}", graph),
+        r#"{"bar": (2, []), "baz": (3, [("foo", 1)]), "foo": (1, [("bar", 2)])}"#
+    );
+
+    let mut cloned = graph.clone();
+    assert_eq!(cloned, graph);
+    cloned.connect_to("baz", vec!["bar"]);
+    assert_ne!(cloned, graph);
+    cloned.disconnect_from("baz", vec!["bar"]);
+    assert_eq!(cloned, graph);
+
+    let mut reordered = FixedSizeHashGraphMap::<String, u64, 31>::new();
+    reordered.extend([
+        (("baz".to_string(), 3), vec![("foo".to_string(), 1)]),
+        (("foo".to_string(), 1), vec![("bar".to_string(), 2), ("bar".to_string(), 2)]),
+    ]);
+    assert_eq!(reordered, graph);
+
+    let err = FixedSizeHashGraphMap::<String, u64, 3>::try_from_iter([
+        (("foo".to_string(), 1), vec![("bar".to_string(), 2)]),
+        (("baz".to_string(), 3), vec![("bat".to_string(), 4)]),
+    ])
+    .unwrap_err();
+    assert_eq!(err.connection, Some(0));
+}
@@ -1,8 +1,13 @@
 #![cfg(test)]
-use std::hash::Hasher;
+use std::cell::Cell;
+use std::hash::{Hash, Hasher};
 
-use crate::{FixedSizeHashMap, OutOfCapacityError};
+use crate::{
+    FixedSizeHashMap, InsertError, OutOfCapacityError,
+    probe::{DoubleHashProbe, LinearProbe, Probe, QuadraticProbe, RobinHoodProbe},
+};
 
+#[derive(Default)]
 struct HighCollisionHasher {}
 
 impl Hasher for HighCollisionHasher {
@@ -13,97 +18,408 @@ impl Hasher for HighCollisionHasher {
     fn write(&mut self, _: &[u8]) {}
 }
 
-impl Default for HighCollisionHasher {
-    fn default() -> Self {
-        HighCollisionHasher {}
-    }
+// Every probing strategy has to behave like a map when all keys collide.
+macro_rules! high_collision_probe_tests {
+    ($probe:ty) => {
+        use super::*;
+
+        type MyHighCollisionMap = FixedSizeHashMap<String, String, 7, HighCollisionHasher, $probe>;
+
+        fn add_some_data(map: &mut MyHighCollisionMap, num: i32) {
+            let keys = ["foo", "bar", "baz", "bat", "boo", "fat", "qux"];
+            for (i, key) in keys.iter().enumerate() {
+                if i as i32 == num {
+                    break;
+                }
+                let _ = map.insert(String::from(*key), ((i as u64 + 1) * 100).to_string());
+            }
+        }
+
+        #[test]
+        fn insert_and_get_colliding_items() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+
+            add_some_data(&mut high_collision_map, 3);
+
+            assert_eq!(high_collision_map.size(), 3);
+            assert!(
+                high_collision_map.exists(&String::from("foo"))
+                    && high_collision_map.exists(&String::from("bar"))
+                    && high_collision_map.exists(&String::from("baz"))
+            );
+            assert_eq!(
+                high_collision_map.get(&String::from("foo")),
+                Some(&"100".to_string())
+            );
+            assert_eq!(
+                high_collision_map.get(&String::from("bar")),
+                Some(&"200".to_string())
+            );
+            assert_eq!(
+                high_collision_map.get(&String::from("baz")),
+                Some(&"300".to_string())
+            );
+            assert_eq!(
+                high_collision_map.tail(),
+                Some((&String::from("baz"), &"300".to_string()))
+            );
+            assert_eq!(
+                high_collision_map.head(),
+                Some((&String::from("foo"), &"100".to_string()))
+            );
+        }
+
+        #[test]
+        fn update_items() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 4);
+            assert_eq!(high_collision_map.size(), 4);
+
+            let old_val = high_collision_map.insert(String::from("bar"), String::from("2000"));
+
+            assert_eq!(high_collision_map.size(), 4);
+            assert!(high_collision_map.get(&String::from("bar")) == Some(&"2000".to_string()));
+            assert_eq!(old_val.unwrap(), Some("200".to_string()));
+        }
+
+        #[test]
+        fn remove_items_from_middle() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 5);
+            assert!(high_collision_map.size() == 5);
+
+            let old_val_of_bar = high_collision_map.remove(&String::from("baz"));
+            let old_val_of_baz = high_collision_map.remove(&String::from("bat"));
+
+            assert_eq!(high_collision_map.size(), 3);
+            assert_eq!(old_val_of_bar, Some("300".to_string()));
+            assert_eq!(old_val_of_baz, Some("400".to_string()));
+            assert!(!high_collision_map.exists(&String::from("baz")));
+            assert!(!high_collision_map.exists(&String::from("bat")));
+            assert_eq!(high_collision_map[&String::from("foo")], "100".to_string());
+            assert_eq!(high_collision_map[&String::from("bar")], "200".to_string());
+            assert_eq!(high_collision_map[&String::from("boo")], "500".to_string());
+        }
+
+        #[test]
+        fn out_of_capacity_error() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 7);
+            assert!(high_collision_map.size() == 7);
+
+            let res = high_collision_map.insert("uhuh".to_string(), "wtf".to_string());
+
+            let err = res.unwrap_err();
+            assert_eq!(OutOfCapacityError::from(err.clone()), OutOfCapacityError { capacity: 7 });
+            assert_eq!(err.into_inner(), ("uhuh".to_string(), "wtf".to_string()));
+        }
+
+        // Inserting used to stop at the first tombstone and add a second copy of a key stored
+        // further along the probe sequence.
+        #[test]
+        fn reinsert_after_removing_colliding_item() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 3);
+
+            let _ = high_collision_map.remove(&String::from("foo"));
+            let old_val = high_collision_map.insert(String::from("baz"), String::from("3000"));
+
+            assert_eq!(old_val.unwrap(), Some("300".to_string()));
+            assert_eq!(high_collision_map.size(), 2);
+            assert_eq!(high_collision_map.iter_head().count(), 2);
+            assert_eq!(high_collision_map.remove(&String::from("baz")), Some("3000".to_string()));
+            assert!(!high_collision_map.exists(&String::from("baz")));
+        }
+
+        #[test]
+        fn removals_leave_tombstones_until_compacted() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 4);
+
+            let _ = high_collision_map.remove(&String::from("bar"));
+            let expected_tombstones = if <$probe as Probe>::ROBIN_HOOD { 0 } else { 1 };
+            assert_eq!(high_collision_map.tombstone_count(), expected_tombstones);
+
+            high_collision_map.compact();
+
+            assert_eq!(high_collision_map.tombstone_count(), 0);
+            assert_eq!(high_collision_map.size(), 3);
+            let keys: Vec<&String> = high_collision_map.iter_head().map(|(k, _)| k).collect();
+            assert_eq!(keys, vec!["foo", "baz", "bat"]);
+            let keys: Vec<&String> = high_collision_map.iter_tail().map(|(k, _)| k).collect();
+            assert_eq!(keys, vec!["bat", "baz", "foo"]);
+            assert_eq!(high_collision_map[&String::from("bat")], "400".to_string());
+        }
+
+        #[test]
+        fn churn_compacts_automatically() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 3);
+
+            for i in 0..100 {
+                let key = format!("churn{}", i);
+                assert!(high_collision_map.insert(key.clone(), i.to_string()).is_ok());
+                assert_eq!(high_collision_map.remove(&key), Some(i.to_string()));
+                assert!(high_collision_map.tombstone_count() <= 1);
+            }
+
+            assert_eq!(high_collision_map.size(), 3);
+            assert_eq!(
+                high_collision_map.head(),
+                Some((&String::from("foo"), &"100".to_string()))
+            );
+            assert_eq!(
+                high_collision_map.tail(),
+                Some((&String::from("baz"), &"300".to_string()))
+            );
+            assert!(!high_collision_map.exists(&String::from("churn0")));
+        }
+
+        #[test]
+        fn churn_on_full_table_reuses_tombstones() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 7);
+            let expected_tombstones = if <$probe as Probe>::ROBIN_HOOD { 0 } else { 1 };
+
+            let mut victim = String::from("foo");
+            for i in 0..100 {
+                assert!(high_collision_map.remove(&victim).is_some());
+                assert_eq!(high_collision_map.tombstone_count(), expected_tombstones);
+                assert!(!high_collision_map.exists(&victim));
+
+                victim = format!("churn{}", i);
+                assert!(high_collision_map.insert(victim.clone(), i.to_string()).is_ok());
+                assert_eq!(high_collision_map.tombstone_count(), 0);
+                assert_eq!(high_collision_map.size(), 7);
+            }
+
+            for key in ["bar", "baz", "bat", "boo", "fat", "qux", "churn99"] {
+                assert!(high_collision_map.exists(&String::from(key)));
+            }
+            assert_eq!(high_collision_map.get(&String::from("churn99")), Some(&"99".to_string()));
+        }
+
+        #[test]
+        fn retain_and_extract_if_keep_lookups_working() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 4);
+
+            high_collision_map.retain(|key, _| key != "foo");
+            assert_eq!(high_collision_map.get(&String::from("bat")), Some(&"400".to_string()));
+
+            let extracted: Vec<(String, String)> =
+                high_collision_map.extract_if(|key, _| key == "baz").collect();
+            assert_eq!(extracted, vec![("baz".to_string(), "300".to_string())]);
+
+            assert_eq!(high_collision_map.size(), 2);
+            assert!(!high_collision_map.exists(&String::from("foo")));
+            assert_eq!(high_collision_map.get(&String::from("bar")), Some(&"200".to_string()));
+            assert_eq!(high_collision_map.get(&String::from("bat")), Some(&"400".to_string()));
+            let keys: Vec<&String> = high_collision_map.iter_head().map(|(k, _)| k).collect();
+            assert_eq!(keys, vec!["bar", "bat"]);
+        }
+
+        #[test]
+        fn swap_remove_and_positions() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 4);
+
+            assert_eq!(high_collision_map.swap_remove(&String::from("foo")), Some("100".to_string()));
+            assert_eq!(high_collision_map.index_of(&String::from("bat")), Some(0));
+            assert_eq!(high_collision_map.get_index(2), Some((&String::from("baz"), &"300".to_string())));
+            assert_eq!(high_collision_map.pop_back(), Some(("baz".to_string(), "300".to_string())));
+            assert_eq!(high_collision_map.get(&String::from("bar")), Some(&"200".to_string()));
+        }
+
+        #[test]
+        fn reorder_and_sort() {
+            let mut high_collision_map = MyHighCollisionMap::new();
+            add_some_data(&mut high_collision_map, 4);
+
+            assert!(high_collision_map.move_to_front(&String::from("baz")));
+            assert!(high_collision_map.insert_after(&String::from("baz"), "qux".to_string(), "700".to_string()).is_ok());
+            let keys: Vec<&String> = high_collision_map.keys().collect();
+            assert_eq!(keys, vec!["baz", "qux", "foo", "bar", "bat"]);
+
+            high_collision_map.sort_by(|k1, _, k2, _| k1.cmp(k2));
+            let keys: Vec<&String> = high_collision_map.keys().collect();
+            assert_eq!(keys, vec!["bar", "bat", "baz", "foo", "qux"]);
+            assert_eq!(high_collision_map.remove(&String::from("bat")), Some("400".to_string()));
+            assert_eq!(high_collision_map.get(&String::from("qux")), Some(&"700".to_string()));
+        }
+    };
 }
 
-type MyHighCollisionMap = FixedSizeHashMap<String, String, 7, HighCollisionHasher>;
+mod linear_probe {
+    high_collision_probe_tests!(LinearProbe);
+}
+
+mod quadratic_probe {
+    high_collision_probe_tests!(QuadraticProbe);
+}
 
-fn add_some_data(map: &mut MyHighCollisionMap, num: i32) {
-    let keys = ["foo", "bar", "baz", "bat", "boo", "fat", "qux"];
-    for (i, key) in keys.iter().enumerate() {
-        if i as i32 == num {
-            break;
+mod double_hash_probe {
+    high_collision_probe_tests!(DoubleHashProbe);
+}
+
+mod robin_hood_probe {
+    high_collision_probe_tests!(RobinHoodProbe);
+}
+
+#[derive(Default)]
+struct IdentityHasher {
+    _hash: u64,
+}
+
+impl Hasher for IdentityHasher {
+    fn finish(&self) -> u64 {
+        self._hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self._hash = (self._hash << 8) | *b as u64;
         }
-        let _ = map.insert(String::from(*key), ((i as u64 + 1) * 100).to_string());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self._hash = i;
     }
 }
 
+type MyRobinHoodMap = FixedSizeHashMap<u64, u64, 13, IdentityHasher, RobinHoodProbe>;
+
 #[test]
-fn insert_and_get_colliding_items() {
-    let mut high_collision_map = MyHighCollisionMap::new();
+fn robin_hood_displaces_entries_closer_to_home() {
+    let mut robin_hood_map = MyRobinHoodMap::new();
+
+    // 0, 13 and 26 share home slot 0, 1 and 14 share home slot 1.
+    for key in [0, 13, 1, 14, 26, 2] {
+        assert!(robin_hood_map.insert(key, key * 10).is_ok());
+    }
 
-    add_some_data(&mut high_collision_map, 3);
+    for key in [0, 13, 1, 14, 26, 2] {
+        assert_eq!(robin_hood_map.get(&key), Some(&(key * 10)));
+    }
+    assert_eq!(robin_hood_map.get(&39), None);
+    assert_eq!(robin_hood_map.get(&27), None);
 
-    assert_eq!(high_collision_map.size(), 3);
-    assert!(
-        high_collision_map.exists(&String::from("foo"))
-            && high_collision_map.exists(&String::from("bar"))
-            && high_collision_map.exists(&String::from("baz"))
-    );
-    assert_eq!(
-        high_collision_map.get(&String::from("foo")),
-        Some(&"100".to_string())
-    );
-    assert_eq!(
-        high_collision_map.get(&String::from("bar")),
-        Some(&"200".to_string())
-    );
-    assert_eq!(
-        high_collision_map.get(&String::from("baz")),
-        Some(&"300".to_string())
-    );
-    assert_eq!(
-        high_collision_map.tail(),
-        Some((&String::from("baz"), &"300".to_string()))
-    );
+    let keys: Vec<u64> = robin_hood_map.iter_head().map(|(k, _)| *k).collect();
+    assert_eq!(keys, vec![0, 13, 1, 14, 26, 2]);
+    let keys: Vec<u64> = robin_hood_map.iter_tail().map(|(k, _)| *k).collect();
+    assert_eq!(keys, vec![2, 26, 14, 1, 13, 0]);
+
+    assert_eq!(robin_hood_map.remove(&13), Some(130));
+    assert_eq!(robin_hood_map.get(&26), Some(&260));
+    assert_eq!(robin_hood_map.get(&2), Some(&20));
+    robin_hood_map.compact();
+    let keys: Vec<u64> = robin_hood_map.iter_head().map(|(k, _)| *k).collect();
+    assert_eq!(keys, vec![0, 1, 14, 26, 2]);
+}
+
+#[test]
+fn robin_hood_fills_every_slot() {
+    let mut robin_hood_map = MyRobinHoodMap::new();
+
+    for key in 0..13 {
+        assert!(robin_hood_map.insert(key * 13 % 17, key).is_ok());
+    }
     assert_eq!(
-        high_collision_map.head(),
-        Some((&String::from("foo"), &"100".to_string()))
+        robin_hood_map.insert(100, 100),
+        Err(InsertError { capacity: 13, key: 100, value: 100 })
     );
+    for key in 0..13 {
+        assert_eq!(robin_hood_map.get(&(key * 13 % 17)), Some(&key));
+    }
+    assert_eq!(robin_hood_map.iter_head().count(), 13);
+}
+
+#[test]
+fn probing_strategies_visit_every_slot() {
+    fn visited<P: Probe>(hash: u64, capacity: usize) -> usize {
+        let mut seen = vec![false; capacity];
+        for step in 0..capacity {
+            seen[P::index(hash, step, capacity)] = true;
+        }
+        seen.iter().filter(|s| **s).count()
+    }
+
+    for hash in [0, 3, 12345, u64::MAX] {
+        assert_eq!(visited::<LinearProbe>(hash, 13), 13);
+        assert_eq!(visited::<DoubleHashProbe>(hash, 13), 13);
+        assert_eq!(visited::<RobinHoodProbe>(hash, 13), 13);
+        assert_eq!(visited::<QuadraticProbe>(hash, 151), 151);
+        // Primes with C % 4 == 1 need the non-residue half of the sequence.
+        for capacity in [2, 5, 13, 17, 97, 101] {
+            assert_eq!(visited::<QuadraticProbe>(hash, capacity), capacity);
+        }
+    }
 }
 
 #[test]
-fn update_items() {
-    let mut high_collision_map = MyHighCollisionMap::new();
-    add_some_data(&mut high_collision_map, 4);
-    assert_eq!(high_collision_map.size(), 4);
+fn quadratic_probe_fills_table_with_capacity_one_mod_four() {
+    let mut map = FixedSizeHashMap::<u64, u64, 13, HighCollisionHasher, QuadraticProbe>::new();
 
-    let old_val = high_collision_map.insert(String::from("bar"), String::from("2000"));
+    for key in 0..13 {
+        assert!(map.insert(key, key).is_ok());
+    }
+    assert_eq!(map.insert(13, 13), Err(InsertError { capacity: 13, key: 13, value: 13 }));
+    for key in 0..13 {
+        assert_eq!(map.get(&key), Some(&key));
+    }
+}
 
-    assert_eq!(high_collision_map.size(), 4);
-    assert!(high_collision_map.get(&String::from("bar")) == Some(&"2000".to_string()));
-    assert_eq!(old_val.unwrap(), Some("200".to_string()));
+thread_local! {
+    static KEY_COMPARISONS: Cell<usize> = const { Cell::new(0) };
+    static KEY_HASHES: Cell<usize> = const { Cell::new(0) };
+}
+
+struct CountedKey(u64);
+
+impl PartialEq for CountedKey {
+    fn eq(&self, other: &Self) -> bool {
+        KEY_COMPARISONS.with(|c| c.set(c.get() + 1));
+        self.0 == other.0
+    }
+}
+
+impl Eq for CountedKey {}
+
+impl Hash for CountedKey {
+    fn hash<S: Hasher>(&self, state: &mut S) {
+        KEY_HASHES.with(|c| c.set(c.get() + 1));
+        state.write_u64(self.0);
+    }
 }
 
 #[test]
-fn remove_items_from_middle() {
-    let mut high_collision_map = MyHighCollisionMap::new();
-    add_some_data(&mut high_collision_map, 5);
-    assert!(high_collision_map.size() == 5);
+fn cached_hash_is_compared_before_key() {
+    let mut map = FixedSizeHashMap::<CountedKey, u64, 7, IdentityHasher>::new();
 
-    let old_val_of_bar = high_collision_map.remove(&String::from("baz"));
-    let old_val_of_baz = high_collision_map.remove(&String::from("bat"));
+    // All three keys share home slot 0 but have different full hashes.
+    for key in [0, 7, 14] {
+        assert!(map.insert(CountedKey(key), key).is_ok());
+    }
+    KEY_COMPARISONS.with(|c| c.set(0));
 
-    assert_eq!(high_collision_map.size(), 3);
-    assert_eq!(old_val_of_bar, Some("300".to_string()));
-    assert_eq!(old_val_of_baz, Some("400".to_string()));
-    assert_eq!(high_collision_map.exists(&String::from("baz")), false);
-    assert_eq!(high_collision_map.exists(&String::from("bat")), false);
-    assert_eq!(high_collision_map[&String::from("foo")], "100".to_string());
-    assert_eq!(high_collision_map[&String::from("bar")], "200".to_string());
-    assert_eq!(high_collision_map[&String::from("boo")], "500".to_string());
+    assert_eq!(map.get(&CountedKey(14)), Some(&14));
+    assert_eq!(map.get(&CountedKey(21)), None);
+    assert_eq!(KEY_COMPARISONS.with(|c| c.get()), 1);
 }
 
 #[test]
-fn out_of_capacity_error() {
-    let mut high_collision_map = MyHighCollisionMap::new();
-    add_some_data(&mut high_collision_map, 7);
-    assert!(high_collision_map.size() == 7);
+fn compaction_reuses_cached_hash() {
+    let mut map = FixedSizeHashMap::<CountedKey, u64, 7, IdentityHasher>::new();
+    for key in [0, 7, 14, 1] {
+        assert!(map.insert(CountedKey(key), key).is_ok());
+    }
+    assert_eq!(map.remove(&CountedKey(7)), Some(7));
+    KEY_HASHES.with(|c| c.set(0));
 
-    let res = high_collision_map.insert("uhuh".to_string(), "wtf".to_string());
+    map.compact();
 
-    assert_eq!(res.unwrap_err(), OutOfCapacityError { capacity: 7 })
+    assert_eq!(KEY_HASHES.with(|c| c.get()), 0);
+    assert_eq!(map.tombstone_count(), 0);
+    assert_eq!(map.get(&CountedKey(14)), Some(&14));
+    assert_eq!(map.get(&CountedKey(1)), Some(&1));
 }
@@ -1,6 +1,6 @@
 #![cfg(test)]
 
-use crate::FixedSizeHashMap;
+use crate::{FixedSizeHashMap, InsertError, hash_map::Entry};
 
 type MyMap = FixedSizeHashMap<String, u64, 13>;
 
@@ -19,10 +19,10 @@ fn insert_and_get_items() {
     let mut fixed_size_map = MyMap::new();
     assert!(fixed_size_map.capacity() == 13);
     assert!(fixed_size_map.size() == 0);
-    assert!(fixed_size_map.exists(&String::from("foo")) == false);
-    assert!(fixed_size_map.get(&String::from("foo")) == None);
-    assert!(fixed_size_map.head() == None);
-    assert!(fixed_size_map.tail() == None);
+    assert!(!fixed_size_map.exists(&String::from("foo")));
+    assert!(fixed_size_map.get(&String::from("foo")).is_none());
+    assert!(fixed_size_map.head().is_none());
+    assert!(fixed_size_map.tail().is_none());
 
     add_some_data(&mut fixed_size_map, 3);
 
@@ -64,8 +64,8 @@ fn remove_items_from_middle() {
     assert_eq!(fixed_size_map.size(), 2);
     assert_eq!(old_val_of_bar, Some(200));
     assert_eq!(old_val_of_baz, Some(300));
-    assert_eq!(fixed_size_map.exists(&String::from("bar")), false);
-    assert_eq!(fixed_size_map.exists(&String::from("zoo")), false);
+    assert!(!fixed_size_map.exists(&String::from("bar")));
+    assert!(!fixed_size_map.exists(&String::from("zoo")));
     assert_eq!(fixed_size_map.tail(), Some((&String::from("bat"), &400)));
     assert_eq!(fixed_size_map.head(), Some((&String::from("foo"), &100)));
 }
@@ -104,10 +104,9 @@ fn in_place_update() {
     add_some_data(&mut fixed_size_map, 4);
     assert!(fixed_size_map.size() == 4);
 
-    fixed_size_map.get_mut(&String::from("bar")).and_then(|v| {
+    if let Some(v) = fixed_size_map.get_mut(&String::from("bar")) {
         *v += 1000;
-        Some(true)
-    });
+    }
     assert_eq!(fixed_size_map.get(&String::from("bar")), Some(&1200));
 }
 
@@ -174,3 +173,490 @@ fn insert_same_item_multiple_times() {
 
     assert_eq!(mymap[&5], 3)
 }
+
+#[test]
+fn update_item_moves_it_to_back() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 4);
+
+    let _ = fixed_size_map.insert(String::from("bar"), 2000);
+
+    let items: Vec<(&String, &u64)> = fixed_size_map.iter_head().collect();
+    assert_eq!(
+        items,
+        vec![
+            (&String::from("foo"), &100),
+            (&String::from("baz"), &300),
+            (&String::from("bat"), &400),
+            (&String::from("bar"), &2000),
+        ]
+    );
+    assert_eq!(fixed_size_map.iter_tail().count(), 4);
+    assert_eq!(fixed_size_map.head(), Some((&String::from("foo"), &100)));
+}
+
+// Updating a middle entry used to link it after the tail without unlinking it first, which left
+// its neighbours pointing at it and broke backward iteration.
+#[test]
+fn updating_middle_item_relinks_both_directions() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 4);
+
+    let _ = fixed_size_map.insert(String::from("baz"), 3000);
+    let keys: Vec<&String> = fixed_size_map.iter_tail().map(|(k, _)| k).collect();
+    assert_eq!(keys, vec!["baz", "bat", "bar", "foo"]);
+
+    assert_eq!(fixed_size_map.remove("bar"), Some(200));
+    let keys: Vec<&String> = fixed_size_map.iter_head().map(|(k, _)| k).collect();
+    assert_eq!(keys, vec!["foo", "bat", "baz"]);
+    let keys: Vec<&String> = fixed_size_map.iter_tail().map(|(k, _)| k).collect();
+    assert_eq!(keys, vec!["baz", "bat", "foo"]);
+}
+
+#[test]
+fn entry_or_insert() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 2);
+
+    assert_eq!(fixed_size_map.entry(String::from("foo")).or_insert(1), Ok(&mut 100));
+    assert_eq!(fixed_size_map.entry(String::from("zoo")).or_insert(1), Ok(&mut 1));
+    assert_eq!(
+        fixed_size_map.entry(String::from("qux")).or_insert_with(|| 7),
+        Ok(&mut 7)
+    );
+    *fixed_size_map.entry(String::from("qux")).or_default().unwrap() += 1;
+
+    assert_eq!(fixed_size_map.size(), 4);
+    assert_eq!(fixed_size_map.get(&String::from("qux")), Some(&8));
+    assert_eq!(fixed_size_map.tail(), Some((&String::from("qux"), &8)));
+}
+
+#[test]
+fn entry_and_modify() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 2);
+
+    let _ = fixed_size_map
+        .entry(String::from("foo"))
+        .and_modify(|v| *v += 1)
+        .or_insert(0);
+    let _ = fixed_size_map
+        .entry(String::from("zoo"))
+        .and_modify(|v| *v += 1)
+        .or_insert(0);
+
+    assert_eq!(fixed_size_map.get(&String::from("foo")), Some(&101));
+    assert_eq!(fixed_size_map.get(&String::from("zoo")), Some(&0));
+}
+
+#[test]
+fn occupied_entry_insert_and_remove() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 3);
+
+    match fixed_size_map.entry(String::from("foo")) {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), &String::from("foo"));
+            assert_eq!(entry.insert(1000), 100);
+            assert_eq!(entry.get(), &1000);
+        }
+        Entry::Vacant(_) => panic!("foo should be in the map"),
+    }
+    assert_eq!(fixed_size_map.head(), Some((&String::from("foo"), &1000)));
+
+    match fixed_size_map.entry(String::from("bar")) {
+        Entry::Occupied(entry) => {
+            assert_eq!(entry.remove_entry(), (String::from("bar"), 200));
+        }
+        Entry::Vacant(_) => panic!("bar should be in the map"),
+    }
+    assert_eq!(fixed_size_map.size(), 2);
+    assert!(!fixed_size_map.exists(&String::from("bar")));
+
+    match fixed_size_map.entry(String::from("bar")) {
+        Entry::Occupied(_) => panic!("bar should have been removed"),
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), &String::from("bar"));
+            assert_eq!(entry.insert(2), Ok(&mut 2));
+        }
+    }
+    assert_eq!(fixed_size_map.tail(), Some((&String::from("bar"), &2)));
+}
+
+#[test]
+fn vacant_entry_respects_capacity() {
+    let mut mymap = FixedSizeHashMap::<u64, u32, 3>::new();
+    for i in 0..3 {
+        assert!(mymap.entry(i).or_insert(1).is_ok());
+    }
+
+    assert_eq!(
+        mymap.entry(10).or_insert(1),
+        Err(InsertError { capacity: 3, key: 10, value: 1 })
+    );
+    assert_eq!(mymap.entry(2).or_insert(1), Ok(&mut 1));
+    assert_eq!(mymap.size(), 3);
+}
+
+#[test]
+fn borrowed_key_lookups() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 4);
+
+    assert!(fixed_size_map.exists("foo"));
+    assert_eq!(fixed_size_map.get("bar"), Some(&200));
+    assert_eq!(fixed_size_map["baz"], 300);
+
+    fixed_size_map["baz"] += 1;
+    if let Some(v) = fixed_size_map.get_mut("bat") {
+        *v += 1;
+    }
+
+    assert_eq!(fixed_size_map.remove("baz"), Some(301));
+    assert_eq!(fixed_size_map.get("bat"), Some(&401));
+    assert!(!fixed_size_map.exists("zoo"));
+}
+
+#[test]
+fn capacity_in_the_tens_of_millions() {
+    // A placeholder does not allocate its slots, this only checks the capacity is accepted.
+    let large_map = FixedSizeHashMap::<u64, u64, 50_000_017>::placeholder();
+    assert_eq!(large_map.capacity(), 50_000_017);
+    assert_eq!(large_map.size(), 0);
+}
+
+#[test]
+fn grow_into_keeps_order() {
+    let mut small_map = FixedSizeHashMap::<u64, u64, 5>::new();
+    for i in 0..5 {
+        let _ = small_map.insert(i, i * 10);
+    }
+    let _ = small_map.remove(&2);
+    let _ = small_map.insert(5, 50);
+    assert_eq!(small_map.insert(6, 60), Err(InsertError { capacity: 5, key: 6, value: 60 }));
+
+    let mut big_map = small_map.grow_into::<11>();
+    assert_eq!(big_map.capacity(), 11);
+    assert_eq!(big_map.size(), 5);
+    assert_eq!(big_map.tombstone_count(), 0);
+    assert_eq!(big_map.insert(6, 60), Ok(None));
+
+    assert_eq!(
+        big_map.iter_head().map(|(k, v)| (*k, *v)).collect::<Vec<_>>(),
+        vec![(0, 0), (1, 10), (3, 30), (4, 40), (5, 50), (6, 60)]
+    );
+    assert_eq!(big_map.get(&3), Some(&30));
+}
+
+#[test]
+fn rejected_insert_returns_key_and_value() {
+    #[derive(Debug)]
+    struct NotClone(u64);
+
+    let mut mymap = FixedSizeHashMap::<u64, NotClone, 2>::new();
+    let _ = mymap.insert(1, NotClone(10));
+    let _ = mymap.insert(2, NotClone(20));
+
+    let err = mymap.insert(3, NotClone(30)).unwrap_err();
+    assert_eq!(err.capacity, 2);
+    let (key, value) = err.into_inner();
+    assert_eq!((key, value.0), (3, 30));
+}
+
+#[test]
+fn iterate_from_both_ends() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 4);
+
+    let mut iter = fixed_size_map.iter_head();
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.next(), Some((&String::from("foo"), &100)));
+    assert_eq!(iter.next_back(), Some((&String::from("bat"), &400)));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next_back(), Some((&String::from("baz"), &300)));
+    assert_eq!(iter.next(), Some((&String::from("bar"), &200)));
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    assert_eq!(
+        fixed_size_map.keys().rev().cloned().collect::<Vec<_>>(),
+        vec!["bat", "baz", "bar", "foo"]
+    );
+    assert_eq!(fixed_size_map.values().copied().collect::<Vec<_>>(), vec![100, 200, 300, 400]);
+}
+
+#[test]
+fn mutate_values_in_place() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 3);
+
+    for (_, value) in fixed_size_map.iter_mut() {
+        *value += 1;
+    }
+    fixed_size_map.values_mut().rev().take(1).for_each(|v| *v *= 2);
+    for (key, value) in &mut fixed_size_map {
+        if key == "foo" {
+            *value = 0;
+        }
+    }
+
+    assert_eq!(
+        (&fixed_size_map).into_iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("foo", 0), ("bar", 201), ("baz", 602)]
+    );
+}
+
+#[test]
+fn into_iter_and_drain() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 4);
+    let _ = fixed_size_map.remove("bar");
+
+    let drained = fixed_size_map.drain().collect::<Vec<_>>();
+    assert_eq!(
+        drained.iter().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("foo", 100), ("baz", 300), ("bat", 400)]
+    );
+    assert_eq!(fixed_size_map.size(), 0);
+    assert_eq!(fixed_size_map.tombstone_count(), 0);
+    assert_eq!(fixed_size_map.head(), None);
+
+    add_some_data(&mut fixed_size_map, 5);
+    let mut drain = fixed_size_map.drain();
+    assert_eq!(drain.next(), Some((String::from("foo"), 100)));
+    drop(drain);
+    assert_eq!(fixed_size_map.size(), 0);
+    assert_eq!(fixed_size_map.iter_head().count(), 0);
+
+    add_some_data(&mut fixed_size_map, 3);
+    let mut iter = fixed_size_map.into_iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next_back(), Some((String::from("baz"), 300)));
+    assert_eq!(
+        iter.collect::<Vec<_>>(),
+        vec![(String::from("foo"), 100), (String::from("bar"), 200)]
+    );
+}
+
+#[test]
+fn leaked_drain_leaves_map_empty() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 3);
+    let _ = fixed_size_map.remove("bar");
+
+    std::mem::forget(fixed_size_map.drain());
+    assert_eq!(fixed_size_map.size(), 0);
+    assert_eq!(fixed_size_map.get("foo"), None);
+    assert_eq!(fixed_size_map.remove("baz"), None);
+
+    add_some_data(&mut fixed_size_map, 2);
+    assert_eq!(fixed_size_map.remove("foo"), Some(100));
+    assert_eq!(fixed_size_map.keys().collect::<Vec<_>>(), vec!["bar"]);
+}
+
+#[test]
+fn retain_extract_if_and_clear() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 6);
+
+    fixed_size_map.retain(|key, value| {
+        *value += 1;
+        key != "foo" && key != "fat"
+    });
+    assert_eq!(fixed_size_map.size(), 4);
+    assert_eq!(fixed_size_map.head(), Some((&String::from("bar"), &201)));
+    assert_eq!(fixed_size_map.tail(), Some((&String::from("boo"), &501)));
+
+    let extracted = fixed_size_map
+        .extract_if(|_, value| *value % 200 == 1)
+        .collect::<Vec<_>>();
+    assert_eq!(extracted, vec![(String::from("bar"), 201), (String::from("bat"), 401)]);
+    assert_eq!(
+        fixed_size_map.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("baz", 301), ("boo", 501)]
+    );
+    assert_eq!(fixed_size_map.get("baz"), Some(&301));
+
+    let mut extract = fixed_size_map.extract_if(|_, _| true);
+    assert_eq!(extract.next(), Some((String::from("baz"), 301)));
+    drop(extract);
+    assert_eq!(fixed_size_map.size(), 1);
+    assert_eq!(fixed_size_map.head(), fixed_size_map.tail());
+
+    fixed_size_map.clear();
+    assert_eq!(fixed_size_map.size(), 0);
+    assert_eq!(fixed_size_map.tombstone_count(), 0);
+    assert_eq!(fixed_size_map.head(), None);
+    assert_eq!(fixed_size_map.tail(), None);
+    add_some_data(&mut fixed_size_map, 2);
+    assert_eq!(fixed_size_map.iter_head().count(), 2);
+}
+
+#[test]
+fn clone_debug_and_compare() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 3);
+
+    let mut cloned = fixed_size_map.clone();
+    assert_eq!(cloned, fixed_size_map);
+    assert_eq!(format!("{:?}", cloned), r#"{"foo": 100, "bar": 200, "baz": 300}"#);
+
+    let _ = cloned.remove("foo");
+    assert_ne!(cloned, fixed_size_map);
+    let _ = cloned.insert(String::from("foo"), 100);
+    assert_eq!(format!("{:?}", cloned), r#"{"bar": 200, "baz": 300, "foo": 100}"#);
+    assert_eq!(cloned, fixed_size_map);
+
+    cloned["foo"] = 101;
+    assert_ne!(cloned, fixed_size_map);
+    assert_eq!(fixed_size_map["foo"], 100);
+}
+
+#[test]
+fn extend_and_collect() {
+    let mut fixed_size_map = MyMap::new();
+    fixed_size_map.extend([(String::from("foo"), 1), (String::from("bar"), 2)]);
+    assert_eq!(fixed_size_map.size(), 2);
+
+    let collected =
+        FixedSizeHashMap::<u64, u64, 7>::try_from_iter((0..5).map(|i| (i, i * 10))).unwrap();
+    assert_eq!(collected.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+
+    let full = FixedSizeHashMap::<u64, u64, 3>::try_from_iter((0..5).map(|i| (i, i)));
+    assert_eq!(full.unwrap_err(), InsertError { capacity: 3, key: 3, value: 3 });
+
+    let mut small_map = FixedSizeHashMap::<u64, u64, 3>::try_from_iter([(0, 0), (1, 1)]).unwrap();
+    let mut rest = (2..5).map(|i| (i, i));
+    assert!(small_map.try_extend(&mut rest).is_err());
+    assert_eq!(small_map.size(), 3);
+    assert_eq!(rest.next(), Some((4, 4)));
+}
+
+#[test]
+#[should_panic]
+fn extend_past_capacity_panics() {
+    let mut small_map = FixedSizeHashMap::<u64, u64, 3>::new();
+    small_map.extend((0..4).map(|i| (i, i)));
+}
+
+#[test]
+fn positional_access() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 5);
+
+    assert_eq!(fixed_size_map.get_index(0), Some((&String::from("foo"), &100)));
+    assert_eq!(fixed_size_map.get_index(3), Some((&String::from("bat"), &400)));
+    assert_eq!(fixed_size_map.get_index(4), Some((&String::from("boo"), &500)));
+    assert_eq!(fixed_size_map.get_index(5), None);
+    if let Some((_, value)) = fixed_size_map.get_index_mut(1) {
+        *value += 1;
+    }
+    assert_eq!(fixed_size_map["bar"], 201);
+
+    assert_eq!(fixed_size_map.index_of("foo"), Some(0));
+    assert_eq!(fixed_size_map.index_of("bat"), Some(3));
+    assert_eq!(fixed_size_map.index_of("zoo"), None);
+    assert_eq!(fixed_size_map.first(), Some((&String::from("foo"), &100)));
+    assert_eq!(fixed_size_map.last(), Some((&String::from("boo"), &500)));
+
+    assert_eq!(fixed_size_map.pop_front(), Some((String::from("foo"), 100)));
+    assert_eq!(fixed_size_map.pop_back(), Some((String::from("boo"), 500)));
+    assert_eq!(fixed_size_map.index_of("bar"), Some(0));
+    assert_eq!(fixed_size_map.size(), 3);
+}
+
+#[test]
+fn swap_and_shift_remove() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 5);
+
+    assert_eq!(fixed_size_map.swap_remove("bar"), Some(200));
+    assert_eq!(
+        fixed_size_map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        vec!["foo", "boo", "baz", "bat"]
+    );
+    assert_eq!(fixed_size_map.swap_remove("foo"), Some(100));
+    assert_eq!(
+        fixed_size_map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        vec!["bat", "boo", "baz"]
+    );
+    assert_eq!(fixed_size_map.swap_remove("baz"), Some(300));
+    assert_eq!(fixed_size_map.last(), Some((&String::from("boo"), &500)));
+    assert_eq!(fixed_size_map.swap_remove("baz"), None);
+
+    assert_eq!(fixed_size_map.shift_remove("bat"), Some(400));
+    assert_eq!(fixed_size_map.keys().map(|k| k.as_str()).collect::<Vec<_>>(), vec!["boo"]);
+}
+
+#[test]
+fn reorder_entries() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 5);
+
+    assert!(fixed_size_map.move_to_front("baz"));
+    assert!(fixed_size_map.move_to_back("foo"));
+    assert!(!fixed_size_map.move_to_back("zoo"));
+    assert!(fixed_size_map.move_before("boo", "bar"));
+    assert!(!fixed_size_map.move_before("boo", "zoo"));
+    assert_eq!(
+        fixed_size_map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        vec!["baz", "boo", "bar", "bat", "foo"]
+    );
+
+    assert_eq!(fixed_size_map.insert_after("baz", String::from("fat"), 600), Ok(None));
+    assert_eq!(fixed_size_map.insert_after("bat", String::from("baz"), 301), Ok(Some(300)));
+    assert_eq!(
+        fixed_size_map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        vec!["fat", "boo", "bar", "bat", "baz", "foo"]
+    );
+    assert_eq!(fixed_size_map.tail(), Some((&String::from("foo"), &100)));
+}
+
+#[test]
+fn sort_relinks_entries() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 6);
+
+    fixed_size_map.sort_by(|k1, _, k2, _| k1.cmp(k2));
+    assert_eq!(
+        fixed_size_map.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        vec!["bar", "bat", "baz", "boo", "fat", "foo"]
+    );
+    assert_eq!(fixed_size_map.iter_tail().next(), Some((&String::from("foo"), &100)));
+
+    fixed_size_map.sort_by_key(|_, v| std::cmp::Reverse(*v));
+    assert_eq!(
+        fixed_size_map.values().copied().collect::<Vec<_>>(),
+        vec![600, 500, 400, 300, 200, 100]
+    );
+    assert_eq!(fixed_size_map.head(), Some((&String::from("fat"), &600)));
+    assert_eq!(fixed_size_map.remove("boo"), Some(500));
+    assert_eq!(fixed_size_map.index_of("bat"), Some(1));
+}
+
+#[test]
+fn overwrite_keeps_first_insertion_order() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 3);
+
+    assert_eq!(fixed_size_map.insert_keep_order(String::from("foo"), 101), Ok(Some(100)));
+    assert_eq!(fixed_size_map.insert_keep_order(String::from("bat"), 400), Ok(None));
+    assert_eq!(
+        fixed_size_map.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("foo", 101), ("bar", 200), ("baz", 300), ("bat", 400)]
+    );
+}
+
+#[test]
+fn overwrite_moves_to_back_in_last_write_order() {
+    let mut fixed_size_map = MyMap::new();
+    add_some_data(&mut fixed_size_map, 3);
+
+    assert_eq!(fixed_size_map.upsert_to_back(String::from("foo"), 101), Ok(Some(100)));
+    assert_eq!(fixed_size_map.insert(String::from("bar"), 201), Ok(Some(200)));
+    assert_eq!(fixed_size_map.upsert_to_back(String::from("bat"), 400), Ok(None));
+    assert_eq!(
+        fixed_size_map.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("baz", 300), ("foo", 101), ("bar", 201), ("bat", 400)]
+    );
+}
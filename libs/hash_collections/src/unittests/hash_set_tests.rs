@@ -22,9 +22,9 @@ fn insert_and_get_items() {
     let mut fixed_size_set = MySet::new();
     assert!(fixed_size_set.capacity() == 13);
     assert!(fixed_size_set.size() == 0);
-    assert!(fixed_size_set.exists(&String::from("foo")) == false);
-    assert!(fixed_size_set.head() == None);
-    assert!(fixed_size_set.tail() == None);
+    assert!(!fixed_size_set.exists(&String::from("foo")));
+    assert!(fixed_size_set.head().is_none());
+    assert!(fixed_size_set.tail().is_none());
 
     add_some_data(&mut fixed_size_set, 3);
 
@@ -50,8 +50,8 @@ fn remove_items_from_middle() {
     assert!(fixed_size_set.remove(&String::from("baz")));
 
     assert_eq!(fixed_size_set.size(), 2);
-    assert_eq!(fixed_size_set.exists(&String::from("bar")), false);
-    assert_eq!(fixed_size_set.exists(&String::from("zoo")), false);
+    assert!(!fixed_size_set.exists(&String::from("bar")));
+    assert!(!fixed_size_set.exists(&String::from("zoo")));
     assert_eq!(fixed_size_set.tail(), Some(&String::from("bat")));
     assert_eq!(fixed_size_set.head(), Some(&String::from("foo")));
 }
@@ -76,7 +76,7 @@ fn remove_non_existent_item() {
     add_some_data(&mut fixed_size_set, 4);
     assert!(fixed_size_set.size() == 4);
 
-    assert_eq!(fixed_size_set.remove(&String::from("zoo")), false);
+    assert!(!fixed_size_set.remove(&String::from("zoo")));
 
     assert_eq!(fixed_size_set.size(), 4);
     assert_eq!(fixed_size_set.tail(), Some(&String::from("bat")));
@@ -129,9 +129,305 @@ fn forward_iteration() {
 fn insert_same_item_multiple_times() {
     let mut myset = FixedSizeHashSet::<u64, 13>::new();
 
-    assert!(myset.insert(5).is_ok_and(|r| r==true));
-    assert!(myset.insert(5).is_ok_and(|r| r==false));
-    assert!(myset.insert(5).is_ok_and(|r| r==false));
+    assert_eq!(myset.insert(5), Ok(true));
+    assert_eq!(myset.insert(5), Ok(false));
+    assert_eq!(myset.insert(5), Ok(false));
 
     assert_eq!(myset.size(), 1)
 }
+
+#[test]
+fn insert_into_full_set() {
+    let mut myset = FixedSizeHashSet::<u64, 3>::new();
+    for i in 0..3 {
+        assert_eq!(myset.insert(i), Ok(true));
+    }
+
+    assert_eq!(myset.insert(1), Ok(false));
+    assert!(myset.insert(3).is_err());
+    assert_eq!(myset.size(), 3)
+}
+
+#[test]
+fn borrowed_item_lookups() {
+    let mut fixed_size_set = MySet::new();
+    add_some_data(&mut fixed_size_set, 4);
+
+    assert!(fixed_size_set.exists("foo"));
+    assert!(fixed_size_set.remove("bar"));
+    assert!(!fixed_size_set.remove("bar"));
+    assert_eq!(fixed_size_set.size(), 3);
+}
+
+#[test]
+fn grow_into_keeps_order() {
+    let mut small_set = FixedSizeHashSet::<u64, 3>::new();
+    for i in 0..3 {
+        let _ = small_set.insert(i);
+    }
+    assert!(small_set.insert(3).is_err());
+
+    let mut big_set = small_set.grow_into::<7>();
+    assert_eq!(big_set.insert(3), Ok(true));
+    assert_eq!(big_set.capacity(), 7);
+    assert_eq!(big_set.iter_head().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn into_iter_and_drain() {
+    let mut fixed_size_set = MySet::new();
+    add_some_data(&mut fixed_size_set, 4);
+
+    let mut iter = (&fixed_size_set).into_iter();
+    assert_eq!(iter.next_back(), Some(&String::from("bat")));
+    assert_eq!(iter.len(), 3);
+
+    let drained = fixed_size_set.drain().collect::<Vec<_>>();
+    assert_eq!(drained, vec!["foo", "bar", "baz", "bat"]);
+    assert_eq!(fixed_size_set.size(), 0);
+    assert_eq!(fixed_size_set.head(), None);
+
+    add_some_data(&mut fixed_size_set, 3);
+    assert_eq!(fixed_size_set.into_iter().rev().collect::<Vec<_>>(), vec!["baz", "bar", "foo"]);
+}
+
+#[test]
+fn retain_extract_if_and_clear() {
+    let mut fixed_size_set = MySet::new();
+    add_some_data(&mut fixed_size_set, 6);
+
+    fixed_size_set.retain(|item| item.starts_with('b'));
+    assert_eq!(fixed_size_set.iter_head().collect::<Vec<_>>(), vec!["bar", "baz", "bat", "boo"]);
+
+    let extracted = fixed_size_set.extract_if(|item| item.ends_with('o')).collect::<Vec<_>>();
+    assert_eq!(extracted, vec!["boo"]);
+    assert_eq!(fixed_size_set.tail(), Some(&String::from("bat")));
+
+    fixed_size_set.clear();
+    assert_eq!(fixed_size_set.size(), 0);
+    assert_eq!(fixed_size_set.head(), None);
+    assert!(!fixed_size_set.exists("bar"));
+}
+
+#[test]
+fn clone_debug_compare_and_collect() {
+    let mut fixed_size_set = MySet::new();
+    add_some_data(&mut fixed_size_set, 3);
+
+    let mut cloned = fixed_size_set.clone();
+    assert_eq!(format!("{:?}", cloned), r#"{"foo", "bar", "baz"}"#);
+    assert!(cloned.remove("foo"));
+    assert_ne!(cloned, fixed_size_set);
+    cloned.extend([String::from("foo")]);
+    assert_eq!(cloned, fixed_size_set);
+
+    let collected = FixedSizeHashSet::<u64, 7>::try_from_iter(0..5).unwrap();
+    assert_eq!(collected.size(), 5);
+    let err = FixedSizeHashSet::<u64, 3>::try_from_iter(0..5).unwrap_err();
+    assert_eq!(err.into_inner(), (3, ()));
+}
+
+#[test]
+fn positional_access_and_swap_remove() {
+    let mut fixed_size_set = MySet::new();
+    add_some_data(&mut fixed_size_set, 4);
+
+    assert_eq!(fixed_size_set.get_index(2), Some(&String::from("baz")));
+    assert_eq!(fixed_size_set.index_of("bat"), Some(3));
+    assert_eq!(fixed_size_set.first(), Some(&String::from("foo")));
+    assert_eq!(fixed_size_set.last(), Some(&String::from("bat")));
+
+    assert!(fixed_size_set.swap_remove("foo"));
+    assert_eq!(fixed_size_set.iter_head().collect::<Vec<_>>(), vec!["bat", "bar", "baz"]);
+    assert!(fixed_size_set.shift_remove("bar"));
+    assert!(!fixed_size_set.shift_remove("bar"));
+
+    assert_eq!(fixed_size_set.pop_back(), Some(String::from("baz")));
+    assert_eq!(fixed_size_set.pop_front(), Some(String::from("bat")));
+    assert_eq!(fixed_size_set.pop_front(), None);
+}
+
+#[test]
+fn reorder_and_sort_items() {
+    let mut fixed_size_set = MySet::new();
+    add_some_data(&mut fixed_size_set, 4);
+
+    assert!(fixed_size_set.move_to_front("bat"));
+    assert!(fixed_size_set.move_to_back("foo"));
+    assert!(fixed_size_set.move_before("baz", "bar"));
+    assert!(!fixed_size_set.move_to_front("zoo"));
+    assert_eq!(fixed_size_set.iter_head().collect::<Vec<_>>(), vec!["bat", "baz", "bar", "foo"]);
+
+    assert_eq!(fixed_size_set.insert_after("bat", String::from("boo")), Ok(true));
+    assert_eq!(fixed_size_set.insert_after("boo", String::from("foo")), Ok(false));
+    assert_eq!(fixed_size_set.insert_after("zoo", String::from("bar")), Ok(false));
+    assert_eq!(
+        fixed_size_set.iter_head().collect::<Vec<_>>(),
+        vec!["bat", "boo", "foo", "baz", "bar"]
+    );
+
+    fixed_size_set.sort_by(|a, b| a.cmp(b));
+    assert_eq!(
+        fixed_size_set.iter_head().collect::<Vec<_>>(),
+        vec!["bar", "bat", "baz", "boo", "foo"]
+    );
+    fixed_size_set.sort_by_key(|item| item.chars().last());
+    assert_eq!(
+        fixed_size_set.iter_head().collect::<Vec<_>>(),
+        vec!["boo", "foo", "bar", "bat", "baz"]
+    );
+    assert_eq!(fixed_size_set.tail(), Some(&String::from("baz")));
+}
+
+#[test]
+fn lazy_set_algebra_follows_left_order() {
+    let left = MySet::try_from_iter(["foo", "bar", "baz", "bat"].iter().map(|s| s.to_string())).unwrap();
+    let right = MySet::try_from_iter(["boo", "bat", "fat", "foo"].iter().map(|s| s.to_string())).unwrap();
+
+    assert_eq!(
+        left.union(&right).collect::<Vec<_>>(),
+        vec!["foo", "bar", "baz", "bat", "boo", "fat"]
+    );
+    assert_eq!(left.intersection(&right).collect::<Vec<_>>(), vec!["foo", "bat"]);
+    assert_eq!(right.intersection(&left).collect::<Vec<_>>(), vec!["bat", "foo"]);
+    assert_eq!(left.difference(&right).collect::<Vec<_>>(), vec!["bar", "baz"]);
+    assert_eq!(
+        left.symmetric_difference(&right).collect::<Vec<_>>(),
+        vec!["bar", "baz", "boo", "fat"]
+    );
+
+    let subset = MySet::try_from_iter(["bat", "foo"].iter().map(|s| s.to_string())).unwrap();
+    assert!(subset.is_subset(&left) && subset.is_subset(&right));
+    assert!(left.is_superset(&subset));
+    assert!(!left.is_subset(&right));
+    assert!(!left.is_disjoint(&right));
+    assert!(left.difference(&right).all(|item| !right.exists(item)));
+    let others = MySet::try_from_iter(["zoo"].iter().map(|s| s.to_string())).unwrap();
+    assert!(others.is_disjoint(&left));
+}
+
+#[test]
+fn set_operators_check_capacity() {
+    let left = FixedSizeHashSet::<u64, 5>::try_from_iter(0..5).unwrap();
+    let right = FixedSizeHashSet::<u64, 7>::try_from_iter(3..10).unwrap();
+
+    let union = &left | &right;
+    assert_eq!(union.unwrap_err().capacity, 5);
+    let intersection = (&left & &right).unwrap();
+    assert_eq!(intersection.iter_head().copied().collect::<Vec<_>>(), vec![3, 4]);
+    assert_eq!((&left - &right).unwrap().iter_head().copied().collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert!((&right ^ &left).is_err());
+
+    let union = left.union_into::<11>(&right).unwrap();
+    assert_eq!(union.capacity(), 11);
+    assert_eq!(union.iter_head().copied().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+    let symmetric_difference = right.symmetric_difference_into::<13>(&left).unwrap();
+    assert_eq!(
+        symmetric_difference.iter_head().copied().collect::<Vec<_>>(),
+        vec![5, 6, 7, 8, 9, 0, 1, 2]
+    );
+    assert!(left.intersection_into::<2>(&right).is_ok());
+    assert!(left.difference_into::<2>(&right).is_err());
+}
+
+#[derive(Debug, Clone)]
+struct Tagged {
+    id: u32,
+    payload: &'static str,
+}
+
+impl PartialEq for Tagged {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Tagged {}
+
+impl std::hash::Hash for Tagged {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl std::borrow::Borrow<u32> for Tagged {
+    fn borrow(&self) -> &u32 {
+        &self.id
+    }
+}
+
+fn tagged_set() -> FixedSizeHashSet<Tagged, 7> {
+    let mut tagged_set = FixedSizeHashSet::new();
+    let _ = tagged_set.insert(Tagged { id: 1, payload: "one" });
+    let _ = tagged_set.insert(Tagged { id: 2, payload: "two" });
+    let _ = tagged_set.insert(Tagged { id: 3, payload: "three" });
+    tagged_set
+}
+
+#[test]
+fn get_returns_stored_item() {
+    let tagged_set = tagged_set();
+
+    assert_eq!(tagged_set.get(&1).map(|t| t.payload), Some("one"));
+    assert_eq!(tagged_set.get(&4).map(|t| t.payload), None);
+}
+
+#[test]
+fn take_unlinks_the_item() {
+    let mut tagged_set = tagged_set();
+
+    assert_eq!(tagged_set.take(&2).map(|t| t.payload), Some("two"));
+    assert_eq!(tagged_set.take(&2).map(|t| t.payload), None);
+    assert_eq!(tagged_set.size(), 2);
+    assert_eq!(tagged_set.iter_head().map(|t| t.id).collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(tagged_set.iter_tail().map(|t| t.id).collect::<Vec<_>>(), vec![3, 1]);
+}
+
+#[test]
+fn replace_keeps_position() {
+    let mut tagged_set = tagged_set();
+
+    let old = tagged_set.replace(Tagged { id: 1, payload: "uno" }).unwrap();
+    assert_eq!(old.map(|t| t.payload), Some("one"));
+    assert_eq!(tagged_set.head().map(|t| t.payload), Some("uno"));
+    assert!(tagged_set.replace(Tagged { id: 4, payload: "four" }).unwrap().is_none());
+    assert_eq!(tagged_set.tail().map(|t| t.payload), Some("four"));
+}
+
+#[test]
+fn replace_on_full_set_returns_rejected_item() {
+    let mut full_set = FixedSizeHashSet::<Tagged, 2>::new();
+    let _ = full_set.insert(Tagged { id: 1, payload: "one" });
+    let _ = full_set.insert(Tagged { id: 2, payload: "two" });
+
+    let err = full_set.replace(Tagged { id: 5, payload: "five" }).unwrap_err();
+    assert_eq!(err.into_inner().0.payload, "five");
+    assert_eq!(full_set.replace(Tagged { id: 2, payload: "zwei" }).unwrap().unwrap().payload, "two");
+}
+
+#[test]
+fn get_or_insert_keeps_stored_item() {
+    let mut tagged_set = tagged_set();
+
+    let existing = tagged_set.get_or_insert(Tagged { id: 2, payload: "zwei" }).unwrap();
+    assert_eq!(existing.payload, "two");
+    let inserted = tagged_set.get_or_insert(Tagged { id: 4, payload: "four" }).unwrap();
+    assert_eq!(inserted.payload, "four");
+    assert_eq!(tagged_set.size(), 4);
+}
+
+#[test]
+fn get_or_insert_with_builds_only_missing_items() {
+    let mut tagged_set = tagged_set();
+
+    let found = tagged_set.get_or_insert_with(&2, |_| unreachable!()).unwrap();
+    assert_eq!(found.payload, "two");
+    let inserted = tagged_set.get_or_insert_with(&4, |id| Tagged { id: *id, payload: "four" });
+    assert_eq!(inserted.map(|t| t.payload).ok(), Some("four"));
+
+    let mut full_set = FixedSizeHashSet::<Tagged, 2>::new();
+    let _ = full_set.insert(Tagged { id: 1, payload: "one" });
+    let _ = full_set.insert(Tagged { id: 2, payload: "two" });
+    let err = full_set.get_or_insert_with(&5, |id| Tagged { id: *id, payload: "five" });
+    assert_eq!(err.unwrap_err().into_inner().0.payload, "five");
+}
@@ -0,0 +1,114 @@
+#![cfg(test)]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::LruCache;
+
+type MyCache = LruCache<String, u64, 3>;
+
+fn keys_of(cache: &MyCache) -> Vec<String> {
+    cache.iter().map(|(k, _)| k.clone()).collect()
+}
+
+#[test]
+fn insert_evicts_least_recently_used() {
+    let mut cache = MyCache::new();
+    assert_eq!(cache.capacity(), 3);
+
+    for (i, key) in ["foo", "bar", "baz", "bat"].iter().enumerate() {
+        assert_eq!(cache.insert(key.to_string(), i as u64), None);
+    }
+
+    assert_eq!(cache.size(), 3);
+    assert!(!cache.exists("foo"));
+    assert_eq!(keys_of(&cache), vec!["bar", "baz", "bat"]);
+}
+
+#[test]
+fn get_promotes_and_peek_does_not() {
+    let mut cache = MyCache::new();
+    let _ = cache.insert("foo".to_string(), 1);
+    let _ = cache.insert("bar".to_string(), 2);
+    let _ = cache.insert("baz".to_string(), 3);
+
+    assert_eq!(cache.get("foo"), Some(&1));
+    assert_eq!(cache.peek("bar"), Some(&2));
+    assert_eq!(keys_of(&cache), vec!["bar", "baz", "foo"]);
+
+    let _ = cache.insert("bat".to_string(), 4);
+    assert!(!cache.exists("bar"));
+    assert_eq!(cache.peek_lru(), Some((&"baz".to_string(), &3)));
+
+    if let Some(v) = cache.get_mut("baz") {
+        *v += 10;
+    }
+    assert_eq!(keys_of(&cache), vec!["foo", "bat", "baz"]);
+    assert_eq!(cache.peek("baz"), Some(&13));
+}
+
+#[test]
+fn updating_existing_key_does_not_evict() {
+    let mut cache = MyCache::new();
+    let _ = cache.insert("foo".to_string(), 1);
+    let _ = cache.insert("bar".to_string(), 2);
+    let _ = cache.insert("baz".to_string(), 3);
+
+    assert_eq!(cache.insert("foo".to_string(), 10), Some(1));
+    assert_eq!(cache.size(), 3);
+    assert_eq!(keys_of(&cache), vec!["bar", "baz", "foo"]);
+}
+
+#[test]
+fn promote_pop_and_remove() {
+    let mut cache = MyCache::new();
+    let _ = cache.insert("foo".to_string(), 1);
+    let _ = cache.insert("bar".to_string(), 2);
+    let _ = cache.insert("baz".to_string(), 3);
+
+    assert!(cache.promote("foo"));
+    assert!(!cache.promote("zoo"));
+    assert_eq!(cache.pop_lru(), Some(("bar".to_string(), 2)));
+    assert_eq!(cache.remove("foo"), Some(1));
+    assert_eq!(cache.pop_lru(), Some(("baz".to_string(), 3)));
+    assert_eq!(cache.pop_lru(), None);
+    assert_eq!(cache.size(), 0);
+}
+
+#[test]
+fn eviction_callback_receives_evicted_entries() {
+    let evicted = Rc::new(RefCell::new(Vec::new()));
+    let mut cache = MyCache::new();
+    let sink = evicted.clone();
+    cache.set_eviction_callback(move |k, v| sink.borrow_mut().push((k, v)));
+
+    for i in 0..6 {
+        let _ = cache.insert(format!("key{}", i), i);
+    }
+    let _ = cache.pop_lru();
+
+    assert_eq!(
+        *evicted.borrow(),
+        vec![
+            ("key0".to_string(), 0),
+            ("key1".to_string(), 1),
+            ("key2".to_string(), 2)
+        ]
+    );
+    assert_eq!(keys_of(&cache), vec!["key4", "key5"]);
+}
+
+#[test]
+fn long_running_eviction_keeps_recent_entries() {
+    let mut cache = LruCache::<u64, u64, 101>::new();
+    for i in 0..10_000 {
+        let _ = cache.insert(i, i);
+        if i % 7 == 0 {
+            let _ = cache.get(&(i / 2));
+        }
+    }
+    assert_eq!(cache.size(), 101);
+    for i in 9_950..10_000 {
+        assert_eq!(cache.peek(&i), Some(&i));
+    }
+}
@@ -1,4 +1,25 @@
+#[cfg(feature = "nightly")]
+mod bi_map_tests;
+#[cfg(feature = "nightly")]
+mod counter_tests;
+mod dyn_capacity_tests;
+#[cfg(feature = "nightly")]
+mod eviction_policy_tests;
+#[cfg(feature = "nightly")]
 mod graph_tests;
+#[cfg(feature = "nightly")]
 mod hash_map_probe_test;
+#[cfg(feature = "nightly")]
 mod hash_map_tests;
-mod hash_set_tests;
\ No newline at end of file
+#[cfg(feature = "nightly")]
+mod hash_set_tests;
+#[cfg(feature = "nightly")]
+mod lru_cache_tests;
+#[cfg(feature = "nightly")]
+mod multi_map_tests;
+#[cfg(all(feature = "nightly", feature = "serde"))]
+mod serde_tests;
+#[cfg(feature = "nightly")]
+mod snapshot_tests;
+#[cfg(feature = "nightly")]
+mod ttl_map_tests;
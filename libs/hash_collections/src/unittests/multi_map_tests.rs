@@ -0,0 +1,89 @@
+#![cfg(test)]
+
+use crate::{FixedSizeMultiMap, probe::RobinHoodProbe};
+
+type MyMultiMap = FixedSizeMultiMap<String, u64, 7>;
+
+fn add_some_data(multi_map: &mut MyMultiMap) {
+    let pairs = [("foo", 1), ("bar", 2), ("foo", 3), ("baz", 4), ("foo", 5)];
+    for (key, value) in pairs {
+        assert!(multi_map.insert(key.to_string(), value).is_ok());
+    }
+}
+
+#[test]
+fn insert_appends_values() {
+    let mut multi_map = MyMultiMap::new();
+    add_some_data(&mut multi_map);
+
+    assert_eq!(multi_map.get_all("foo").copied().collect::<Vec<_>>(), vec![1, 3, 5]);
+    assert_eq!(multi_map.get_all("zoo").count(), 0);
+    assert_eq!(multi_map.len_of("foo"), 3);
+    assert_eq!(multi_map.len_of("zoo"), 0);
+    assert_eq!(multi_map.size(), 5);
+    assert_eq!(multi_map.key_count(), 3);
+    assert_eq!(multi_map.keys().collect::<Vec<_>>(), vec!["foo", "bar", "baz"]);
+    assert_eq!(
+        multi_map.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("foo", 1), ("foo", 3), ("foo", 5), ("bar", 2), ("baz", 4)]
+    );
+    assert_eq!(format!("{:?}", multi_map), r#"{"foo": [1, 3, 5], "bar": [2], "baz": [4]}"#);
+}
+
+#[test]
+fn remove_one_and_remove_all() {
+    let mut multi_map = MyMultiMap::new();
+    add_some_data(&mut multi_map);
+
+    assert_eq!(multi_map.remove_one("foo", &3), Some(3));
+    assert_eq!(multi_map.remove_one("foo", &3), None);
+    assert_eq!(multi_map.remove_one("foo", &5), Some(5));
+    assert!(multi_map.insert(String::from("foo"), 6).is_ok());
+    assert_eq!(multi_map.get_all("foo").copied().collect::<Vec<_>>(), vec![1, 6]);
+
+    assert_eq!(multi_map.remove_one("bar", &2), Some(2));
+    assert!(!multi_map.exists("bar"));
+    assert_eq!(multi_map.remove_all("foo"), vec![1, 6]);
+    assert_eq!(multi_map.remove_all("foo"), Vec::<u64>::new());
+    assert_eq!(multi_map.size(), 1);
+    assert_eq!(multi_map.key_count(), 1);
+}
+
+#[test]
+fn value_pool_bounds_all_keys() {
+    let mut multi_map = MyMultiMap::new();
+    add_some_data(&mut multi_map);
+    assert!(multi_map.insert(String::from("bat"), 6).is_ok());
+    assert!(multi_map.insert(String::from("bar"), 7).is_ok());
+
+    let err = multi_map.insert(String::from("foo"), 8).unwrap_err();
+    assert_eq!(err.capacity, 7);
+    assert_eq!(err.into_inner(), (String::from("foo"), 8));
+
+    // Freed slots are reused by any key.
+    assert_eq!(multi_map.remove_all("foo").len(), 3);
+    let refilled = MyMultiMap::try_from_iter((0..3).map(|i| (String::from("boo"), i)));
+    assert!(refilled.is_ok());
+    multi_map.extend((0..3).map(|i| (String::from("boo"), i)));
+    assert_eq!(multi_map.len_of("boo"), 3);
+    assert_eq!(multi_map.size(), 7);
+
+    multi_map.clear();
+    assert_eq!(multi_map.size(), 0);
+    assert!(multi_map.try_extend((0..7).map(|i| (i.to_string(), i))).is_ok());
+    assert!(MyMultiMap::try_from_iter((0..8).map(|i| (i.to_string(), i))).is_err());
+}
+
+#[test]
+fn robin_hood_keys_keep_their_values() {
+    let mut multi_map = FixedSizeMultiMap::<u64, u64, 11, std::hash::DefaultHasher, RobinHoodProbe>::new();
+    for i in 0..10 {
+        assert!(multi_map.insert(i % 4, i).is_ok());
+    }
+    assert_eq!(multi_map.remove_all(&1), vec![1, 5, 9]);
+    assert_eq!(multi_map.remove_one(&2, &6), Some(6));
+    let cloned = multi_map.clone();
+    assert_eq!(cloned.get_all(&0).copied().collect::<Vec<_>>(), vec![0, 4, 8]);
+    assert_eq!(cloned.get_all(&2).copied().collect::<Vec<_>>(), vec![2]);
+    assert_eq!(cloned.get_all(&3).copied().collect::<Vec<_>>(), vec![3, 7]);
+}
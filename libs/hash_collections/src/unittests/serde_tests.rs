@@ -0,0 +1,77 @@
+#![cfg(test)]
+
+use crate::{FixedSizeHashGraphMap, FixedSizeHashMap, FixedSizeHashSet};
+
+#[test]
+fn map_round_trip_keeps_order() {
+    let mut map = FixedSizeHashMap::<String, u64, 13>::new();
+    for (i, key) in ["foo", "bar", "baz"].iter().enumerate() {
+        let _ = map.insert(key.to_string(), i as u64);
+    }
+
+    let json = serde_json::to_string(&map).unwrap();
+    assert_eq!(json, r#"{"foo":0,"bar":1,"baz":2}"#);
+
+    let loaded: FixedSizeHashMap<String, u64, 13> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.keys().collect::<Vec<_>>(), vec!["foo", "bar", "baz"]);
+    assert_eq!(loaded, map);
+}
+
+#[test]
+fn set_round_trip_keeps_order() {
+    let set = FixedSizeHashSet::<u64, 7>::try_from_iter([5, 1, 3]).unwrap();
+
+    let json = serde_json::to_string(&set).unwrap();
+    assert_eq!(json, "[5,1,3]");
+
+    let loaded: FixedSizeHashSet<u64, 7> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.iter_head().copied().collect::<Vec<_>>(), vec![5, 1, 3]);
+}
+
+#[test]
+fn graph_edges_are_stored_by_key() {
+    let mut graph = FixedSizeHashGraphMap::<String, u64, 31>::new();
+    let _ = graph.insert(
+        ("foo".to_string(), 1),
+        vec![("bar".to_string(), 2), ("baz".to_string(), 3), ("bar".to_string(), 2)],
+    );
+    graph.connect_to("baz", vec!["foo"]);
+
+    let json = serde_json::to_string(&graph).unwrap();
+    assert_eq!(
+        json,
+        r#"[["foo",1,[["bar",2],["baz",1]]],["baz",3,[["foo",1]]],["bar",2,[]]]"#
+    );
+
+    // A different capacity puts the nodes in different slots.
+    let mut loaded: FixedSizeHashGraphMap<String, u64, 97> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.out_edge_weight("foo", "bar"), 2);
+    assert_eq!(loaded.out_edge_weight("foo", "baz"), 1);
+    assert_eq!(loaded.out_edge_weight("baz", "foo"), 1);
+    assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+
+    loaded.remove("foo");
+    assert_eq!(loaded.iter_out_edges("baz").count(), 0);
+}
+
+#[test]
+fn too_small_containers_report_capacity() {
+    let err = serde_json::from_str::<FixedSizeHashMap<u64, u64, 2>>(r#"{"1":1,"2":2,"3":3}"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("capacity of 2 entries"));
+
+    let err = serde_json::from_str::<FixedSizeHashSet<u64, 2>>("[1,2,3]").unwrap_err();
+    assert!(err.to_string().contains("capacity of 2 entries"));
+
+    let err = serde_json::from_str::<FixedSizeHashGraphMap<String, u64, 2>>(
+        r#"[["foo",1,[]],["bar",2,[]],["baz",3,[]]]"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("capacity of 2 entries"));
+
+    let err = serde_json::from_str::<FixedSizeHashGraphMap<String, u64, 3>>(
+        r#"[["foo",1,[["bar",1]]]]"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("missing node"));
+}
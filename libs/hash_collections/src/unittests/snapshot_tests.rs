@@ -0,0 +1,211 @@
+#![cfg(test)]
+
+use std::hash::{DefaultHasher, Hasher};
+
+use crate::{
+    DynFixedHashMap, FixedSizeHashGraphMap, FixedSizeHashMap, SnapshotError, SnapshotView,
+    probe::{LinearProbe, RobinHoodProbe},
+};
+
+#[derive(Default)]
+struct HighCollisionHasher {}
+
+impl Hasher for HighCollisionHasher {
+    fn finish(&self) -> u64 {
+        3
+    }
+
+    fn write(&mut self, _: &[u8]) {}
+}
+
+#[test]
+fn map_round_trip_keeps_slots_and_order() {
+    let mut map = FixedSizeHashMap::<String, u64, 13>::new();
+    for (i, key) in ["foo", "bar", "baz", "bat"].iter().enumerate() {
+        let _ = map.insert(key.to_string(), i as u64);
+    }
+    let _ = map.remove("bar");
+
+    let bytes = map.to_snapshot();
+    let mut loaded = FixedSizeHashMap::<String, u64, 13>::from_snapshot(&bytes).unwrap();
+
+    assert_eq!(loaded, map);
+    assert_eq!(loaded.tombstone_count(), map.tombstone_count());
+    assert_eq!(loaded.keys().collect::<Vec<_>>(), vec!["foo", "baz", "bat"]);
+    assert_eq!(loaded.insert("boo".to_string(), 4), Ok(None));
+    assert_eq!(loaded.tail(), Some((&"boo".to_string(), &4)));
+    assert!(loaded.to_snapshot().len() > bytes.len());
+}
+
+#[test]
+fn pod_snapshot_is_read_in_place() {
+    let mut map = FixedSizeHashMap::<u64, [u32; 2], 31>::new();
+    for i in 0..20 {
+        let _ = map.insert(i * 7, [i as u32, 2 * i as u32]);
+    }
+    let _ = map.remove(&21);
+
+    let bytes = map.to_pod_snapshot();
+    let view = SnapshotView::<u64, [u32; 2]>::new(&bytes).unwrap();
+    assert_eq!(view.capacity(), 31);
+    assert_eq!(view.size(), 19);
+    assert_eq!(view.get(&14), Some([2, 4]));
+    assert_eq!(view.get(&21), None);
+    assert!(!view.exists(&1));
+    assert_eq!(
+        view.iter().map(|(k, _)| k).collect::<Vec<_>>(),
+        map.keys().copied().collect::<Vec<_>>()
+    );
+
+    // The fixed layout loads into an owned map as well.
+    let loaded = FixedSizeHashMap::<u64, [u32; 2], 31>::from_snapshot(&bytes).unwrap();
+    assert_eq!(loaded, map);
+
+    assert_eq!(
+        SnapshotView::<u64, [u32; 2]>::new(&map.to_snapshot()).err(),
+        Some(SnapshotError::LayoutMismatch)
+    );
+    assert_eq!(
+        SnapshotView::<u64, u32>::new(&bytes).err(),
+        Some(SnapshotError::LayoutMismatch)
+    );
+}
+
+#[test]
+fn robin_hood_view_lookups() {
+    let mut map = DynFixedHashMap::<u32, u32, HighCollisionHasher, RobinHoodProbe>::new(7);
+    for i in 0..5 {
+        let _ = map.insert(i, i * 10);
+    }
+    let _ = map.remove(&2);
+
+    let bytes = map.to_pod_snapshot();
+    let view = SnapshotView::<u32, u32, HighCollisionHasher, RobinHoodProbe>::new(&bytes).unwrap();
+    assert_eq!(view.get(&4), Some(40));
+    assert_eq!(view.get(&2), None);
+    assert_eq!(view.iter().collect::<Vec<_>>(), vec![(0, 0), (1, 10), (3, 30), (4, 40)]);
+}
+
+#[test]
+fn graph_round_trip_keeps_edges() {
+    let mut graph = FixedSizeHashGraphMap::<String, bool, 31>::new();
+    let _ = graph.insert(
+        ("foo".to_string(), true),
+        vec![("bar".to_string(), false), ("baz".to_string(), true), ("bar".to_string(), false)],
+    );
+    let _ = graph.insert(("bat".to_string(), false), vec![("foo".to_string(), true)]);
+    graph.remove("baz");
+
+    let bytes = graph.to_snapshot();
+    let mut loaded = FixedSizeHashGraphMap::<String, bool, 31>::from_snapshot(&bytes).unwrap();
+    assert_eq!(loaded, graph);
+    assert_eq!(loaded.out_edge_weight("foo", "bar"), 2);
+    assert_eq!(loaded.out_edge_weight("bat", "foo"), 1);
+
+    loaded.remove("foo");
+    assert_eq!(loaded.iter_out_edges("bat").count(), 0);
+    assert_eq!(loaded.size(), 2);
+}
+
+#[test]
+fn mismatched_snapshots_are_rejected() {
+    let mut map = FixedSizeHashMap::<String, u64, 13>::new();
+    let _ = map.insert("foo".to_string(), 1);
+    let bytes = map.to_snapshot();
+
+    assert_eq!(
+        FixedSizeHashMap::<String, u64, 11>::from_snapshot(&bytes).err(),
+        Some(SnapshotError::CapacityMismatch { expected: 11, found: 13 })
+    );
+    assert_eq!(
+        FixedSizeHashMap::<String, u64, 13, HighCollisionHasher>::from_snapshot(&bytes).err(),
+        Some(SnapshotError::HasherMismatch)
+    );
+    assert_eq!(
+        FixedSizeHashMap::<String, u64, 13, DefaultHasher, RobinHoodProbe>::from_snapshot(&bytes)
+            .err(),
+        Some(SnapshotError::ProbeMismatch)
+    );
+    assert_eq!(
+        FixedSizeHashGraphMap::<String, u64, 13>::from_snapshot(&bytes).err(),
+        Some(SnapshotError::WrongKind)
+    );
+
+    let mut damaged = bytes.clone();
+    *damaged.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        DynFixedHashMap::<String, u64, DefaultHasher, LinearProbe>::from_snapshot(&damaged).err(),
+        Some(SnapshotError::ChecksumMismatch)
+    );
+    assert_eq!(
+        DynFixedHashMap::<String, u64>::from_snapshot(&bytes[..bytes.len() - 1]).err(),
+        Some(SnapshotError::Corrupt)
+    );
+    assert_eq!(
+        DynFixedHashMap::<String, u64>::from_snapshot(b"not a snapshot").err(),
+        Some(SnapshotError::NotASnapshot)
+    );
+}
+
+// Overwrites the capacity field, which sits after the magic and six u32 fields and is not
+// covered by the body checksum.
+fn with_capacity_field(bytes: &[u8], capacity: u64) -> Vec<u8> {
+    let mut crafted = bytes.to_vec();
+    crafted[32..40].copy_from_slice(&capacity.to_le_bytes());
+    crafted
+}
+
+#[test]
+fn crafted_capacities_are_rejected_before_allocating() {
+    let mut map = FixedSizeHashMap::<u64, u64, 13>::new();
+    let _ = map.insert(1, 1);
+
+    let bytes = map.to_snapshot();
+    for capacity in [0, 1 << 50, bytes.len() as u64] {
+        assert_eq!(
+            DynFixedHashMap::<u64, u64>::from_snapshot(&with_capacity_field(&bytes, capacity))
+                .err(),
+            Some(SnapshotError::Corrupt)
+        );
+    }
+
+    let pod_bytes = map.to_pod_snapshot();
+    for capacity in [0, 1 << 50, 12, 14] {
+        let crafted = with_capacity_field(&pod_bytes, capacity);
+        assert_eq!(
+            DynFixedHashMap::<u64, u64>::from_snapshot(&crafted).err(),
+            Some(SnapshotError::Corrupt)
+        );
+        assert_eq!(SnapshotView::<u64, u64>::new(&crafted).err(), Some(SnapshotError::Corrupt));
+    }
+    assert_eq!(
+        DynFixedHashMap::<u64, u64>::from_snapshot(&with_capacity_field(&pod_bytes, 13))
+            .unwrap()
+            .get(&1),
+        Some(&1)
+    );
+}
+
+// Same output as DefaultHasher under another name: the snapshot ids do not depend on type names.
+#[derive(Default)]
+struct RenamedHasher(DefaultHasher);
+
+impl Hasher for RenamedHasher {
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+}
+
+#[test]
+fn hasher_is_identified_by_its_output() {
+    let mut map = FixedSizeHashMap::<u64, u64, 13>::new();
+    let _ = map.insert(1, 10);
+
+    let loaded =
+        FixedSizeHashMap::<u64, u64, 13, RenamedHasher>::from_snapshot(&map.to_snapshot()).unwrap();
+    assert_eq!(loaded.get(&1), Some(&10));
+}
@@ -0,0 +1,138 @@
+#![cfg(test)]
+
+use std::cell::Cell;
+use std::hash::DefaultHasher;
+use std::time::{Duration, Instant};
+
+use crate::{Clock, TtlHashMap, probe::RobinHoodProbe};
+
+struct ManualClock {
+    _now: Cell<Instant>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock {
+            _now: Cell::new(Instant::now()),
+        }
+    }
+}
+
+impl ManualClock {
+    fn advance(&self, by: Duration) {
+        self._now.set(self._now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self._now.get()
+    }
+}
+
+type SessionMap = TtlHashMap<String, u64, 7, ManualClock>;
+
+const SECOND: Duration = Duration::from_secs(1);
+
+#[test]
+fn entries_expire_lazily() {
+    let mut sessions = SessionMap::new();
+    let _ = sessions.insert_with_ttl("foo".to_string(), 1, 10 * SECOND);
+    let _ = sessions.insert_with_ttl("bar".to_string(), 2, 20 * SECOND);
+    let _ = sessions.insert("baz".to_string(), 3);
+
+    sessions.clock().advance(5 * SECOND);
+    assert_eq!(sessions.get("foo"), Some(&1));
+    assert_eq!(sessions.ttl("foo"), Some(5 * SECOND));
+    assert_eq!(sessions.ttl("baz"), None);
+
+    sessions.clock().advance(5 * SECOND);
+    assert_eq!(sessions.get("foo"), None);
+    assert!(!sessions.exists("foo"));
+    assert!(sessions.exists("bar"));
+    assert_eq!(sessions.size(), 2);
+
+    sessions.clock().advance(100 * SECOND);
+    assert!(!sessions.exists("bar"));
+    assert_eq!(sessions.get("baz"), Some(&3));
+    assert_eq!(
+        sessions.iter_head().map(|(k, v)| (k.as_str(), *v)).collect::<Vec<_>>(),
+        vec![("baz", 3)]
+    );
+    assert_eq!(sessions.size(), 1);
+}
+
+#[test]
+fn size_skips_expired_entries() {
+    let mut sessions = SessionMap::new();
+    for (i, key) in ["foo", "bar", "baz"].iter().enumerate() {
+        let _ = sessions.insert_with_ttl(key.to_string(), i as u64, SECOND);
+    }
+    assert_eq!(sessions.size(), 3);
+
+    sessions.clock().advance(SECOND);
+    assert_eq!(sessions.size(), 0);
+    assert_eq!(sessions.iter_head().count(), 0);
+    assert_eq!(sessions.insert("bat".to_string(), 4), Ok(None));
+    assert_eq!(sessions.size(), 1);
+}
+
+#[test]
+fn purge_expired_drops_dead_entries() {
+    let mut sessions = SessionMap::new();
+    for (i, key) in ["foo", "bar", "baz", "bat", "boo"].iter().enumerate() {
+        let _ = sessions.insert_with_ttl(key.to_string(), i as u64, (i as u32 + 1) * SECOND);
+    }
+
+    sessions.clock().advance(3 * SECOND);
+    assert_eq!(sessions.purge_expired(), 3);
+    assert_eq!(sessions.size(), 2);
+    assert_eq!(sessions.purge_expired(), 0);
+    assert_eq!(
+        sessions.iter_head().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+        vec!["bat", "boo"]
+    );
+}
+
+#[test]
+fn reinserting_refreshes_the_deadline() {
+    let mut sessions = SessionMap::new();
+    let _ = sessions.insert_with_ttl("foo".to_string(), 1, 10 * SECOND);
+
+    sessions.clock().advance(8 * SECOND);
+    assert_eq!(sessions.insert_with_ttl("foo".to_string(), 2, 10 * SECOND), Ok(Some(1)));
+
+    sessions.clock().advance(8 * SECOND);
+    assert_eq!(sessions.get("foo"), Some(&2));
+
+    sessions.clock().advance(8 * SECOND);
+    assert_eq!(sessions.insert("foo".to_string(), 3), Ok(None));
+    assert_eq!(sessions.remove("foo"), Some(3));
+}
+
+#[test]
+fn full_map_makes_room_from_expired_entries() {
+    let mut sessions =
+        TtlHashMap::<u64, u64, 5, ManualClock, DefaultHasher, RobinHoodProbe>::new();
+    for i in 0..5 {
+        let _ = sessions.insert_with_ttl(i, i, (i as u32 + 1) * SECOND);
+    }
+    assert!(sessions.insert(5, 5).is_err());
+
+    sessions.clock().advance(2 * SECOND);
+    assert_eq!(sessions.insert(5, 5), Ok(None));
+    assert_eq!(sessions.size(), 4);
+    for i in 2..6 {
+        assert_eq!(sessions.get(&i), Some(&i));
+    }
+}
+
+#[test]
+fn capacity_is_rounded_up_to_a_prime() {
+    let mut sessions = TtlHashMap::<u64, u64, 100, ManualClock>::new();
+    assert_eq!(sessions.capacity(), 101);
+    for i in 0..101 {
+        assert!(sessions.insert(i, i).is_ok());
+    }
+    assert!(sessions.insert(101, 101).is_err());
+}